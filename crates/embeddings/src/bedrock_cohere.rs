@@ -168,6 +168,11 @@ impl BedrockCohereClient {
         Err(final_error)
     }
 
+    /// Dimension of the vectors produced by the Bedrock Cohere v3 embedding models
+    pub fn dimension(&self) -> usize {
+        1024
+    }
+
     async fn try_embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
         let request = BedrockEmbedRequest {
             texts: texts.to_vec(),
@@ -249,6 +254,17 @@ mod tests {
         assert_eq!(client.config.max_retries, 5);
     }
 
+    #[tokio::test]
+    async fn should_report_dimension_for_configured_model() {
+        let config = BedrockCohereConfig {
+            model_id: "cohere.embed-english-v3".to_string(),
+            ..BedrockCohereConfig::default()
+        };
+        let client = BedrockCohereClient::new(config).await.unwrap();
+
+        assert_eq!(client.dimension(), 1024);
+    }
+
     #[tokio::test]
     async fn should_return_empty_embeddings_for_empty_input() {
         let config = BedrockCohereConfig::default();
@@ -1,7 +1,12 @@
+use crate::chain::transient;
 use anyhow::Result;
+use aws_sdk_bedrockruntime::error::SdkError;
+use aws_sdk_bedrockruntime::operation::invoke_model::InvokeModelError;
 use aws_sdk_bedrockruntime::{primitives::Blob, Client as BedrockClient};
 use tracing::error;
 
+/// Invokes a Bedrock model; throttling, timeouts, unavailable models and server
+/// errors are returned as [`crate::TransientError`]s
 pub async fn invoke_bedrock(
     client: &BedrockClient,
     model_id: &str,
@@ -16,7 +21,28 @@ pub async fn invoke_bedrock(
         .await
         .map_err(|e| {
             error!("Bedrock invoke_model failed: {}", e);
-            e
+            if is_transient(&e) {
+                transient(e)
+            } else {
+                anyhow::Error::new(e)
+            }
         })?;
     Ok(response.body().as_ref().to_vec())
 }
+
+fn is_transient<R>(error: &SdkError<InvokeModelError, R>) -> bool {
+    match error {
+        SdkError::ServiceError(service) => matches!(
+            service.err(),
+            InvokeModelError::ThrottlingException(_)
+                | InvokeModelError::ServiceUnavailableException(_)
+                | InvokeModelError::InternalServerException(_)
+                | InvokeModelError::ModelNotReadyException(_)
+                | InvokeModelError::ModelTimeoutException(_)
+        ),
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) | SdkError::ResponseError(_) => {
+            true
+        }
+        _ => false,
+    }
+}
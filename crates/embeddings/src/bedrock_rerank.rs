@@ -0,0 +1,141 @@
+use crate::bedrock_common::invoke_bedrock;
+use anyhow::{Context, Result};
+use aws_config::BehaviorVersion;
+use aws_sdk_bedrockruntime::Client as BedrockClient;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+#[derive(Debug, Clone)]
+pub struct BedrockRerankConfig {
+    pub model_id: String,
+    pub aws_region: String,
+}
+
+impl Default for BedrockRerankConfig {
+    fn default() -> Self {
+        Self {
+            model_id: "cohere.rerank-v3-5:0".to_string(),
+            aws_region: "eu-central-1".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BedrockRerankRequest<'a> {
+    query: &'a str,
+    documents: &'a [String],
+    top_n: usize,
+    api_version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct BedrockRerankResponse {
+    results: Vec<RerankResult>,
+}
+
+/// Relevance of the document at `index` in the request
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RerankResult {
+    pub index: usize,
+    pub relevance_score: f32,
+}
+
+/// Cohere Rerank hosted on Bedrock
+pub struct BedrockRerankClient {
+    config: BedrockRerankConfig,
+    client: BedrockClient,
+}
+
+impl BedrockRerankClient {
+    pub async fn new(config: BedrockRerankConfig) -> Result<Self> {
+        if config.model_id.is_empty() {
+            anyhow::bail!("Rerank model ID cannot be empty");
+        }
+
+        let aws_config = aws_config::defaults(BehaviorVersion::latest())
+            .region(aws_config::Region::new(config.aws_region.clone()))
+            .load()
+            .await;
+        let client = BedrockClient::new(&aws_config);
+
+        Ok(Self { config, client })
+    }
+
+    /// Scores every document against `query`, most relevant first
+    pub async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<RerankResult>> {
+        if documents.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let request = BedrockRerankRequest {
+            query,
+            documents,
+            top_n: documents.len(),
+            api_version: 2,
+        };
+        let request_body = serde_json::to_vec(&request)?;
+        let response_body = invoke_bedrock(&self.client, &self.config.model_id, request_body)
+            .await
+            .context("Bedrock rerank request failed")?;
+
+        parse_rerank_response(&response_body)
+    }
+}
+
+fn parse_rerank_response(body: &[u8]) -> Result<Vec<RerankResult>> {
+    let response: BedrockRerankResponse = serde_json::from_slice(body).inspect_err(|_| {
+        error!(
+            "Failed to parse rerank response: {}",
+            String::from_utf8_lossy(body)
+        );
+    })?;
+    let mut results = response.results;
+    results.sort_by(|a, b| b.relevance_score.total_cmp(&a.relevance_score));
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_serialize_rerank_request_for_bedrock() {
+        let documents = vec!["Leave policy".to_string(), "VPN setup".to_string()];
+        let request = BedrockRerankRequest {
+            query: "parental leave",
+            documents: &documents,
+            top_n: 2,
+            api_version: 2,
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["query"], "parental leave");
+        assert_eq!(json["documents"][1], "VPN setup");
+        assert_eq!(json["api_version"], 2);
+    }
+
+    #[test]
+    fn should_parse_rerank_response_most_relevant_first() {
+        let body = br#"{"id": "x", "results": [
+            {"index": 0, "relevance_score": 0.12},
+            {"index": 1, "relevance_score": 0.87}
+        ]}"#;
+
+        let results = parse_rerank_response(body).unwrap();
+
+        assert_eq!(
+            results,
+            vec![
+                RerankResult {
+                    index: 1,
+                    relevance_score: 0.87
+                },
+                RerankResult {
+                    index: 0,
+                    relevance_score: 0.12
+                }
+            ]
+        );
+    }
+}
@@ -91,6 +91,19 @@ impl BedrockTitanClient {
 
         Ok(parsed.embedding)
     }
+
+    /// Dimension of the vectors this client will produce.
+    /// Titan v2 honours `output_embedding_length` (256, 512 or 1024); v1 is fixed at 1536.
+    pub fn dimension(&self) -> usize {
+        if self
+            .config
+            .model_id
+            .starts_with("amazon.titan-embed-text-v1")
+        {
+            return 1536;
+        }
+        self.config.output_embedding_length.unwrap_or(1024) as usize
+    }
 }
 
 #[cfg(test)]
@@ -107,4 +120,38 @@ mod tests {
         assert!(json.contains("inputText"));
         assert!(json.contains("1024"));
     }
+
+    #[tokio::test]
+    async fn should_report_configured_output_length_as_dimension() {
+        let config = BedrockTitanConfig {
+            output_embedding_length: Some(512),
+            ..BedrockTitanConfig::default()
+        };
+        let client = BedrockTitanClient::new(config).await.unwrap();
+
+        assert_eq!(client.dimension(), 512);
+    }
+
+    #[tokio::test]
+    async fn should_report_default_dimension_when_output_length_unset() {
+        let config = BedrockTitanConfig {
+            output_embedding_length: None,
+            ..BedrockTitanConfig::default()
+        };
+        let client = BedrockTitanClient::new(config).await.unwrap();
+
+        assert_eq!(client.dimension(), 1024);
+    }
+
+    #[tokio::test]
+    async fn should_report_fixed_dimension_for_titan_v1() {
+        let config = BedrockTitanConfig {
+            model_id: "amazon.titan-embed-text-v1".to_string(),
+            output_embedding_length: None,
+            ..BedrockTitanConfig::default()
+        };
+        let client = BedrockTitanClient::new(config).await.unwrap();
+
+        assert_eq!(client.dimension(), 1536);
+    }
 }
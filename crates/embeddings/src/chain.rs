@@ -0,0 +1,279 @@
+use crate::{EmbedFuture, EmbeddingProvider};
+use anyhow::Result;
+use std::fmt;
+use tracing::warn;
+
+/// A transient provider failure (throttling, timeouts, server errors) worth failing
+/// over on. Providers wrap such errors with [`transient`]; every other error is final.
+#[derive(Debug)]
+pub struct TransientError(anyhow::Error);
+
+impl fmt::Display for TransientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for TransientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Marks `error` as transient, keeping its message and causes
+pub fn transient(error: impl Into<anyhow::Error>) -> anyhow::Error {
+    anyhow::Error::new(TransientError(error.into()))
+}
+
+/// Returns true when an embedding error is transient and another provider may succeed
+pub fn is_retryable_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<TransientError>())
+}
+
+/// Ordered chain of embedding providers that fails over on retryable errors.
+/// Every provider in the chain must produce vectors of the same dimension.
+pub struct ProviderChain {
+    providers: Vec<(String, Box<dyn EmbeddingProvider>)>,
+    dimension: usize,
+}
+
+impl ProviderChain {
+    pub fn new(providers: Vec<(String, Box<dyn EmbeddingProvider>)>) -> Result<Self> {
+        let (first_name, first) = providers
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Embedding provider chain cannot be empty"))?;
+        let dimension = first.dimension();
+
+        for (name, provider) in providers.iter().skip(1) {
+            if provider.dimension() != dimension {
+                anyhow::bail!(
+                    "Embedding provider chain dimension mismatch: '{}' produces {} dimensions but '{}' produces {}",
+                    first_name,
+                    dimension,
+                    name,
+                    provider.dimension()
+                );
+            }
+        }
+
+        Ok(Self {
+            providers,
+            dimension,
+        })
+    }
+
+    /// Names of the providers in failover order
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.providers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let mut last_error = None;
+
+        for (index, (name, provider)) in self.providers.iter().enumerate() {
+            match provider.embed(texts.clone()).await {
+                Ok(embeddings) => return Ok(embeddings),
+                Err(e) => {
+                    let has_next = index + 1 < self.providers.len();
+                    if !has_next || !is_retryable_error(&e) {
+                        return Err(e);
+                    }
+                    warn!(
+                        "Embedding provider '{}' failed with a retryable error, failing over: {}",
+                        name, e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No embedding providers available")))
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+impl EmbeddingProvider for ProviderChain {
+    fn embed(&self, texts: Vec<String>) -> EmbedFuture<'_> {
+        Box::pin(self.embed(texts))
+    }
+    fn dimension(&self) -> usize {
+        self.dimension()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FallbackEmbeddingProvider, OpenAiCompatibleClient, OpenAiCompatibleConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    struct FailingProvider {
+        message: &'static str,
+        retryable: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl EmbeddingProvider for FailingProvider {
+        fn embed(&self, _texts: Vec<String>) -> EmbedFuture<'_> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                let error = anyhow::anyhow!(self.message);
+                Err(if self.retryable {
+                    transient(error)
+                } else {
+                    error
+                })
+            })
+        }
+        fn dimension(&self) -> usize {
+            8
+        }
+    }
+
+    fn failing(
+        message: &'static str,
+        retryable: bool,
+    ) -> (Box<dyn EmbeddingProvider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = FailingProvider {
+            message,
+            retryable,
+            calls: calls.clone(),
+        };
+        (Box::new(provider), calls)
+    }
+
+    #[test]
+    fn should_classify_retryable_errors() {
+        let throttled = transient(anyhow::anyhow!("ThrottlingException: rate exceeded"));
+        assert!(is_retryable_error(&throttled));
+        assert_eq!(throttled.to_string(), "ThrottlingException: rate exceeded");
+        assert!(is_retryable_error(
+            &transient(anyhow::anyhow!("request timed out")).context("Failed to embed")
+        ));
+        // Only the error type counts, not words in the message
+        assert!(!is_retryable_error(&anyhow::anyhow!(
+            "400 Bad Request: connection field is invalid"
+        )));
+        assert!(!is_retryable_error(&anyhow::anyhow!(
+            "ThrottlingException: rate exceeded"
+        )));
+    }
+
+    #[tokio::test]
+    async fn should_fail_over_on_openai_compatible_server_errors() {
+        for status in [500, 502] {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .respond_with(ResponseTemplate::new(status))
+                .mount(&server)
+                .await;
+            let primary = OpenAiCompatibleClient::new(OpenAiCompatibleConfig {
+                base_url: server.uri(),
+                dimensions: Some(8),
+                max_retries: 0,
+                ..OpenAiCompatibleConfig::default()
+            })
+            .await
+            .unwrap();
+            let chain = ProviderChain::new(vec![
+                ("primary".to_string(), Box::new(primary)),
+                (
+                    "secondary".to_string(),
+                    Box::new(FallbackEmbeddingProvider::new(8)),
+                ),
+            ])
+            .unwrap();
+
+            let result = chain.embed(vec!["hello".to_string()]).await;
+
+            assert_eq!(result.unwrap().len(), 1, "no failover on HTTP {}", status);
+        }
+    }
+
+    #[test]
+    fn should_reject_empty_chain() {
+        assert!(ProviderChain::new(vec![]).is_err());
+    }
+
+    #[test]
+    fn should_reject_chain_with_mismatched_dimensions() {
+        let result = ProviderChain::new(vec![
+            (
+                "primary".to_string(),
+                Box::new(FallbackEmbeddingProvider::new(1024)),
+            ),
+            (
+                "secondary".to_string(),
+                Box::new(FallbackEmbeddingProvider::new(512)),
+            ),
+        ]);
+
+        let error = result.err().unwrap().to_string();
+        assert!(error.contains("'primary' produces 1024"));
+        assert!(error.contains("'secondary' produces 512"));
+    }
+
+    #[tokio::test]
+    async fn should_fail_over_on_retryable_error() {
+        let (primary, primary_calls) = failing("ThrottlingException", true);
+        let chain = ProviderChain::new(vec![
+            ("primary".to_string(), primary),
+            (
+                "secondary".to_string(),
+                Box::new(FallbackEmbeddingProvider::new(8)),
+            ),
+        ])
+        .unwrap();
+
+        let result = chain.embed(vec!["hello".to_string()]).await.unwrap();
+
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 8);
+        assert_eq!(chain.provider_names(), vec!["primary", "secondary"]);
+    }
+
+    #[tokio::test]
+    async fn should_not_fail_over_on_non_retryable_error() {
+        let (primary, _) = failing("ValidationException: bad request", false);
+        let (secondary, secondary_calls) = failing("ThrottlingException", true);
+        let chain = ProviderChain::new(vec![
+            ("primary".to_string(), primary),
+            ("secondary".to_string(), secondary),
+        ])
+        .unwrap();
+
+        let result = chain.embed(vec!["hello".to_string()]).await;
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("ValidationException"));
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn should_return_last_error_when_all_providers_fail() {
+        let (primary, _) = failing("ThrottlingException", true);
+        let (secondary, _) = failing("request timed out", true);
+        let chain = ProviderChain::new(vec![
+            ("primary".to_string(), primary),
+            ("secondary".to_string(), secondary),
+        ])
+        .unwrap();
+
+        let result = chain.embed(vec!["hello".to_string()]).await;
+
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+    }
+}
@@ -1,10 +1,77 @@
+use crate::tokenizer::{TokenCounter, WhitespaceTokenCounter};
+use std::ops::Range;
+use std::sync::Arc;
+
+mod code;
+
+pub use code::CodeLanguage;
+
 /// A text chunk with metadata
 #[derive(Debug, Clone, PartialEq)]
 pub struct TextChunk {
     pub content: String,
+    /// Byte range of the chunk in the source text
     pub start_pos: usize,
     pub end_pos: usize,
+    /// Character range of the chunk in the source text
+    pub start_char: usize,
+    pub end_char: usize,
+    /// 1-based lines containing the first and last character of the chunk
+    pub start_line: usize,
+    pub end_line: usize,
     pub chunk_id: usize,
+    /// Structural section (Markdown section or code item) the chunk was cut from;
+    /// chunks sharing a section id are consecutive and share a parent span
+    pub section_id: usize,
+    /// Markdown headings or code scopes enclosing the chunk, outermost first
+    pub heading_path: Vec<String>,
+    /// Context describing where the chunk sits (e.g. module path and item signature
+    /// for code), embedded alongside `content` but not part of it
+    pub context_header: Option<String>,
+}
+
+impl TextChunk {
+    /// Text to embed: the context header, if any, followed by the chunk content
+    pub fn embedding_text(&self) -> String {
+        match &self.context_header {
+            Some(header) => format!("{}\n\n{}", header, self.content),
+            None => self.content.clone(),
+        }
+    }
+}
+
+/// How text is split into chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChunkStrategy {
+    /// Fixed windows of whitespace-separated words joined by single spaces; offsets
+    /// still span the original words in the source
+    #[default]
+    WordWindow,
+    /// Splits on Markdown headings, then paragraphs, lines, sentences and words,
+    /// merging neighbouring pieces while preserving the original formatting
+    Recursive,
+    /// Splits source code on top-level items using a parser for the language
+    Code(CodeLanguage),
+}
+
+/// Granularity levels tried in order by the recursive strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SplitLevel {
+    Paragraph,
+    Line,
+    Sentence,
+    Word,
+}
+
+impl SplitLevel {
+    fn finer(self) -> Option<Self> {
+        match self {
+            SplitLevel::Paragraph => Some(SplitLevel::Line),
+            SplitLevel::Line => Some(SplitLevel::Sentence),
+            SplitLevel::Sentence => Some(SplitLevel::Word),
+            SplitLevel::Word => None,
+        }
+    }
 }
 
 /// Configuration for text chunking
@@ -12,6 +79,9 @@ pub struct TextChunk {
 pub struct ChunkConfig {
     pub chunk_size: usize,   // ~500 tokens
     pub overlap_size: usize, // ~100 tokens
+    /// Counter used to measure `chunk_size` and `overlap_size`
+    pub token_counter: Arc<dyn TokenCounter>,
+    pub strategy: ChunkStrategy,
 }
 
 impl Default for ChunkConfig {
@@ -19,6 +89,8 @@ impl Default for ChunkConfig {
         Self {
             chunk_size: 500,
             overlap_size: 100,
+            token_counter: Arc::new(WhitespaceTokenCounter),
+            strategy: ChunkStrategy::WordWindow,
         }
     }
 }
@@ -33,22 +105,49 @@ impl TextChunker {
         Self { config }
     }
 
+    pub fn config(&self) -> &ChunkConfig {
+        &self.config
+    }
+
     pub fn chunk_text(&self, text: &str) -> Vec<TextChunk> {
         if text.is_empty() {
             return vec![];
         }
 
-        let tokens: Vec<&str> = text.split_whitespace().collect();
+        match self.config.strategy {
+            ChunkStrategy::WordWindow => self.chunk_word_window(text),
+            ChunkStrategy::Recursive => self.chunk_recursive(text),
+            ChunkStrategy::Code(language) => self.chunk_code(text, language, ""),
+        }
+    }
+
+    /// Chunks a file, using the code strategy for recognised source file extensions
+    /// and the configured strategy otherwise
+    pub fn chunk_file(&self, file_name: &str, text: &str) -> Vec<TextChunk> {
+        match CodeLanguage::from_path(file_name) {
+            Some(language) if !text.is_empty() => self.chunk_code(text, language, file_name),
+            _ => self.chunk_text(text),
+        }
+    }
+
+    fn chunk_word_window(&self, text: &str) -> Vec<TextChunk> {
+        let tokens = word_ranges(text);
+        let token_costs: Vec<usize> = tokens
+            .iter()
+            .map(|range| {
+                self.config
+                    .token_counter
+                    .count_tokens(&text[range.clone()])
+                    .max(1)
+            })
+            .collect();
         let total_tokens = tokens.len();
 
-        if total_tokens <= self.config.chunk_size {
+        let locator = SourceLocator::new(text);
+
+        if token_costs.iter().sum::<usize>() <= self.config.chunk_size {
             // Text fits in a single chunk
-            return vec![TextChunk {
-                content: text.to_string(),
-                start_pos: 0,
-                end_pos: text.len(),
-                chunk_id: 0,
-            }];
+            return vec![locator.chunk(text.to_string(), 0..text.len(), 0, 0, Vec::new())];
         }
 
         let mut chunks = Vec::new();
@@ -56,36 +155,26 @@ impl TextChunker {
         let mut start_token_idx = 0;
 
         while start_token_idx < total_tokens {
-            let end_token_idx =
-                std::cmp::min(start_token_idx + self.config.chunk_size, total_tokens);
-
-            let chunk_tokens = &tokens[start_token_idx..end_token_idx];
-            let chunk_text = chunk_tokens.join(" ");
-
-            // Calculate character positions
-            let start_pos = if start_token_idx == 0 {
-                0
-            } else {
-                // Find the character position of the start token
-                tokens[0..start_token_idx]
-                    .iter()
-                    .map(|token| token.len() + 1) // +1 for space
-                    .sum::<usize>()
-                    .saturating_sub(1) // Remove last space
-            };
-
-            let end_pos = if end_token_idx == total_tokens {
-                text.len()
-            } else {
-                start_pos + chunk_text.len()
-            };
-
-            chunks.push(TextChunk {
-                content: chunk_text,
-                start_pos,
-                end_pos,
-                chunk_id,
-            });
+            // Take words until the model token budget is reached (always at least one word)
+            let mut end_token_idx = start_token_idx;
+            let mut chunk_cost = 0;
+            while end_token_idx < total_tokens
+                && (end_token_idx == start_token_idx
+                    || chunk_cost + token_costs[end_token_idx] <= self.config.chunk_size)
+            {
+                chunk_cost += token_costs[end_token_idx];
+                end_token_idx += 1;
+            }
+
+            let chunk_text = tokens[start_token_idx..end_token_idx]
+                .iter()
+                .map(|range| &text[range.clone()])
+                .collect::<Vec<_>>()
+                .join(" ");
+            let range = tokens[start_token_idx].start..tokens[end_token_idx - 1].end;
+
+            // Word windows have no structure, so each chunk is its own section
+            chunks.push(locator.chunk(chunk_text, range, chunk_id, chunk_id, Vec::new()));
 
             chunk_id += 1;
 
@@ -94,15 +183,310 @@ impl TextChunker {
                 break;
             }
 
-            start_token_idx = end_token_idx.saturating_sub(self.config.overlap_size);
+            // Step back over trailing words worth up to `overlap_size` tokens, always making progress
+            let mut next_start = end_token_idx;
+            let mut overlap_cost = 0;
+            while next_start > start_token_idx + 1
+                && overlap_cost + token_costs[next_start - 1] <= self.config.overlap_size
+            {
+                next_start -= 1;
+                overlap_cost += token_costs[next_start];
+            }
+            start_token_idx = next_start;
         }
 
         chunks
     }
 
-    /// Simple token approximation: split by whitespace
+    fn chunk_recursive(&self, text: &str) -> Vec<TextChunk> {
+        let locator = SourceLocator::new(text);
+        let mut chunks = Vec::new();
+
+        for (section, heading_path) in markdown_sections(text) {
+            let mut units = Vec::new();
+            self.split_to_fit(text, section, SplitLevel::Paragraph, &mut units);
+            if units.is_empty() {
+                continue;
+            }
+
+            self.merge_units(&locator, &units, &heading_path, None, &mut chunks);
+        }
+
+        chunks
+    }
+
+    /// Greedily merges neighbouring units into chunks within the token budget,
+    /// carrying trailing units worth up to `overlap_size` tokens into the next chunk.
+    /// All chunks produced by one call form a new section.
+    fn merge_units(
+        &self,
+        locator: &SourceLocator,
+        units: &[Range<usize>],
+        heading_path: &[String],
+        context_header: Option<&str>,
+        chunks: &mut Vec<TextChunk>,
+    ) {
+        let costs: Vec<usize> = units
+            .iter()
+            .map(|unit| self.count(&locator.text[unit.clone()]))
+            .collect();
+
+        let section_id = chunks.last().map_or(0, |chunk| chunk.section_id + 1);
+        let mut start = 0;
+        while start < units.len() {
+            // Merge neighbouring units while the budget allows (always at least one)
+            let mut end = start;
+            let mut cost = 0;
+            while end < units.len() && (end == start || cost + costs[end] <= self.config.chunk_size)
+            {
+                cost += costs[end];
+                end += 1;
+            }
+
+            let range = units[start].start..units[end - 1].end;
+            let mut chunk = locator.chunk(
+                locator.text[range.clone()].to_string(),
+                range,
+                chunks.len(),
+                section_id,
+                heading_path.to_vec(),
+            );
+            chunk.context_header = context_header.map(str::to_string);
+            chunks.push(chunk);
+
+            if end >= units.len() {
+                break;
+            }
+
+            let mut next_start = end;
+            let mut overlap_cost = 0;
+            while next_start > start + 1
+                && overlap_cost + costs[next_start - 1] <= self.config.overlap_size
+            {
+                next_start -= 1;
+                overlap_cost += costs[next_start];
+            }
+            start = next_start;
+        }
+    }
+
+    /// Splits `range` into trimmed pieces at `level`, descending to finer levels
+    /// for pieces that are still larger than the chunk budget
+    fn split_to_fit(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        level: SplitLevel,
+        out: &mut Vec<Range<usize>>,
+    ) {
+        for piece in split_at_level(text, range, level) {
+            match level.finer() {
+                Some(finer) if self.count(&text[piece.clone()]) > self.config.chunk_size => {
+                    self.split_to_fit(text, piece, finer, out)
+                }
+                _ => out.push(piece),
+            }
+        }
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.config.token_counter.count_tokens(text).max(1)
+    }
+
+    /// Token count according to the configured token counter
     pub fn estimate_tokens(&self, text: &str) -> usize {
-        text.split_whitespace().count()
+        self.config.token_counter.count_tokens(text)
+    }
+}
+
+/// Maps byte offsets in a source text to character offsets and line numbers
+struct SourceLocator<'a> {
+    text: &'a str,
+    /// Byte offset and character offset of the start of every line
+    line_starts: Vec<(usize, usize)>,
+}
+
+impl<'a> SourceLocator<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![(0, 0)];
+        let mut chars = 0;
+        for (byte, c) in text.char_indices() {
+            chars += 1;
+            if c == '\n' {
+                line_starts.push((byte + 1, chars));
+            }
+        }
+        Self { text, line_starts }
+    }
+
+    /// Index into `line_starts` of the line containing `byte`
+    fn line_index(&self, byte: usize) -> usize {
+        self.line_starts
+            .partition_point(|&(start, _)| start <= byte)
+            .saturating_sub(1)
+    }
+
+    fn char_offset(&self, byte: usize) -> usize {
+        let (line_byte, line_char) = self.line_starts[self.line_index(byte)];
+        line_char + self.text[line_byte..byte].chars().count()
+    }
+
+    fn chunk(
+        &self,
+        content: String,
+        range: Range<usize>,
+        chunk_id: usize,
+        section_id: usize,
+        heading_path: Vec<String>,
+    ) -> TextChunk {
+        // The last line is the one holding the final character, not the position after it
+        let last_byte = range.end.saturating_sub(1).max(range.start);
+        TextChunk {
+            start_char: self.char_offset(range.start),
+            end_char: self.char_offset(range.end),
+            start_line: self.line_index(range.start) + 1,
+            end_line: self.line_index(last_byte) + 1,
+            start_pos: range.start,
+            end_pos: range.end,
+            content,
+            chunk_id,
+            section_id,
+            heading_path,
+            context_header: None,
+        }
+    }
+}
+
+/// Byte ranges of the whitespace-separated words in `text`
+fn word_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut word_start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), word_start) {
+            (true, Some(start)) => {
+                ranges.push(start..i);
+                word_start = None;
+            }
+            (false, None) => word_start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(start) = word_start {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
+
+/// Splits text into sections at Markdown ATX headings (ignoring fenced code blocks),
+/// returning each section's byte range together with its heading path
+fn markdown_sections(text: &str) -> Vec<(Range<usize>, Vec<String>)> {
+    let mut sections = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut section_start = 0;
+    let mut section_path = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            if let Some((level, title)) = parse_heading(trimmed) {
+                if offset > section_start {
+                    sections.push((section_start..offset, section_path.clone()));
+                }
+                stack.retain(|(existing, _)| *existing < level);
+                stack.push((level, title));
+                section_path = stack.iter().map(|(_, title)| title.clone()).collect();
+                section_start = offset;
+            }
+        }
+        offset += line.len();
+    }
+
+    if text.len() > section_start {
+        sections.push((section_start..text.len(), section_path));
+    }
+    sections
+}
+
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') && !rest.starts_with('\t') {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim();
+    Some((level, title.to_string()))
+}
+
+/// Splits `range` at the separators of `level`, returning non-empty pieces trimmed of
+/// surrounding whitespace. Separators between pieces stay in the original text, so
+/// merging neighbouring pieces reproduces the source formatting exactly.
+fn split_at_level(text: &str, range: Range<usize>, level: SplitLevel) -> Vec<Range<usize>> {
+    let slice = &text[range.clone()];
+    let mut boundaries = vec![0];
+
+    match level {
+        SplitLevel::Paragraph => {
+            let mut offset = 0;
+            let mut previous_blank = false;
+            for line in slice.split_inclusive('\n') {
+                let blank = line.trim().is_empty();
+                if blank && !previous_blank {
+                    boundaries.push(offset);
+                }
+                previous_blank = blank;
+                offset += line.len();
+            }
+        }
+        SplitLevel::Line => {
+            boundaries.extend(slice.match_indices('\n').map(|(i, _)| i + 1));
+        }
+        SplitLevel::Sentence => {
+            let mut chars = slice.char_indices().peekable();
+            while let Some((_, c)) = chars.next() {
+                if matches!(c, '.' | '!' | '?') {
+                    if let Some(&(next, next_char)) = chars.peek() {
+                        if next_char.is_whitespace() {
+                            boundaries.push(next);
+                        }
+                    }
+                }
+            }
+        }
+        SplitLevel::Word => {
+            let mut previous_whitespace = false;
+            for (i, c) in slice.char_indices() {
+                let whitespace = c.is_whitespace();
+                if !whitespace && previous_whitespace {
+                    boundaries.push(i);
+                }
+                previous_whitespace = whitespace;
+            }
+        }
+    }
+    boundaries.push(slice.len());
+
+    boundaries
+        .windows(2)
+        .filter_map(|window| trim_range(text, range.start + window[0]..range.start + window[1]))
+        .collect()
+}
+
+fn trim_range(text: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let slice = &text[range.clone()];
+    let leading = slice.len() - slice.trim_start().len();
+    let trimmed = slice.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        let start = range.start + leading;
+        Some(start..start + trimmed.len())
     }
 }
 
@@ -124,6 +508,7 @@ mod tests {
         let config = ChunkConfig {
             chunk_size: 200,
             overlap_size: 50,
+            ..ChunkConfig::default()
         };
         let chunker = TextChunker::new(config.clone());
 
@@ -144,6 +529,7 @@ mod tests {
         let chunker = TextChunker::new(ChunkConfig {
             chunk_size: 10,
             overlap_size: 2,
+            ..ChunkConfig::default()
         });
 
         let text = "This is a short text.";
@@ -161,6 +547,7 @@ mod tests {
         let chunker = TextChunker::new(ChunkConfig {
             chunk_size: 5,   // 5 tokens per chunk
             overlap_size: 2, // 2 tokens overlap
+            ..ChunkConfig::default()
         });
 
         // 12 tokens total: "one two three four five six seven eight nine ten eleven twelve"
@@ -217,6 +604,7 @@ mod tests {
         let chunker = TextChunker::new(ChunkConfig {
             chunk_size: 5,
             overlap_size: 1,
+            ..ChunkConfig::default()
         });
 
         let text = "one two three four five"; // Exactly 5 tokens
@@ -231,6 +619,7 @@ mod tests {
         let chunker = TextChunker::new(ChunkConfig {
             chunk_size: 3,
             overlap_size: 1,
+            ..ChunkConfig::default()
         });
 
         let text = "word1 word2 word3 word4 word5";
@@ -242,4 +631,271 @@ mod tests {
             assert!(!chunk.content.trim().starts_with(' '));
         }
     }
+
+    #[test]
+    fn should_size_chunks_by_model_tokens() {
+        use crate::tokenizer::CharsPerTokenCounter;
+
+        let chunker = TextChunker::new(ChunkConfig {
+            chunk_size: 4,
+            overlap_size: 0,
+            token_counter: Arc::new(CharsPerTokenCounter::new(4.0)),
+            ..ChunkConfig::default()
+        });
+
+        // Each 8-character word costs two tokens, so only two fit per chunk
+        let text = "abcdefgh ijklmnop qrstuvwx yzabcdef";
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].content, "abcdefgh ijklmnop");
+        assert_eq!(chunks[1].content, "qrstuvwx yzabcdef");
+        for chunk in &chunks {
+            assert!(chunker.estimate_tokens(&chunk.content) <= 4);
+        }
+    }
+
+    #[test]
+    fn should_overlap_by_model_tokens() {
+        use crate::tokenizer::CharsPerTokenCounter;
+
+        let chunker = TextChunker::new(ChunkConfig {
+            chunk_size: 4,
+            overlap_size: 2,
+            token_counter: Arc::new(CharsPerTokenCounter::new(4.0)),
+            ..ChunkConfig::default()
+        });
+
+        let text = "abcdefgh ijklmnop qrstuvwx";
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].content, "abcdefgh ijklmnop");
+        assert_eq!(chunks[1].content, "ijklmnop qrstuvwx");
+    }
+
+    #[test]
+    fn should_place_oversized_word_in_its_own_chunk() {
+        use crate::tokenizer::CharsPerTokenCounter;
+
+        let chunker = TextChunker::new(ChunkConfig {
+            chunk_size: 2,
+            overlap_size: 1,
+            token_counter: Arc::new(CharsPerTokenCounter::new(1.0)),
+            ..ChunkConfig::default()
+        });
+
+        let chunks = chunker.chunk_text("a averyveryverylongword b");
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1].content, "averyveryverylongword");
+    }
+
+    #[test]
+    fn should_estimate_tokens_with_configured_counter() {
+        use crate::tokenizer::CharsPerTokenCounter;
+
+        let chunker = TextChunker::new(ChunkConfig {
+            token_counter: Arc::new(CharsPerTokenCounter::new(2.0)),
+            ..ChunkConfig::default()
+        });
+
+        assert_eq!(chunker.estimate_tokens("hello world"), 5);
+    }
+
+    fn recursive_chunker(chunk_size: usize, overlap_size: usize) -> TextChunker {
+        TextChunker::new(ChunkConfig {
+            chunk_size,
+            overlap_size,
+            strategy: ChunkStrategy::Recursive,
+            ..ChunkConfig::default()
+        })
+    }
+
+    #[test]
+    fn should_keep_small_document_formatting_in_recursive_strategy() {
+        let chunker = recursive_chunker(100, 0);
+        let text = "Intro line\n\n- first item\n- second item\n";
+
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0].content,
+            "Intro line\n\n- first item\n- second item"
+        );
+        assert_eq!(
+            &text[chunks[0].start_pos..chunks[0].end_pos],
+            chunks[0].content
+        );
+    }
+
+    #[test]
+    fn should_split_on_headings_and_record_heading_path() {
+        let chunker = recursive_chunker(100, 0);
+        let text = "# Handbook\nWelcome.\n\n## Expenses\nSubmit receipts within 30 days.\n\n### Contractors\nUse the vendor portal.\n\n## Leave\nBook leave in the HR tool.\n";
+
+        let chunks = chunker.chunk_text(text);
+        let paths: Vec<Vec<String>> = chunks.iter().map(|c| c.heading_path.clone()).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                vec!["Handbook".to_string()],
+                vec!["Handbook".to_string(), "Expenses".to_string()],
+                vec![
+                    "Handbook".to_string(),
+                    "Expenses".to_string(),
+                    "Contractors".to_string()
+                ],
+                vec!["Handbook".to_string(), "Leave".to_string()],
+            ]
+        );
+        assert_eq!(
+            chunks[1].content,
+            "## Expenses\nSubmit receipts within 30 days."
+        );
+        let ids: Vec<usize> = chunks.iter().map(|c| c.chunk_id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn should_ignore_headings_inside_code_fences() {
+        let chunker = recursive_chunker(100, 0);
+        let text = "# Setup\n```bash\n# install deps\nmake\n```\n";
+
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].heading_path, vec!["Setup".to_string()]);
+        assert!(chunks[0].content.contains("# install deps"));
+    }
+
+    #[test]
+    fn should_split_long_sections_on_paragraphs_before_sentences() {
+        let chunker = recursive_chunker(8, 0);
+        let text = "One two three four.\n\nFive six seven eight.\n\nNine ten eleven twelve.";
+
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[0].content,
+            "One two three four.\n\nFive six seven eight."
+        );
+        assert_eq!(chunks[1].content, "Nine ten eleven twelve.");
+    }
+
+    #[test]
+    fn should_split_long_paragraph_on_sentence_boundaries() {
+        let chunker = recursive_chunker(5, 0);
+        let text = "The first sentence is here. The second one follows! Is this the third?";
+
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|c| c.content.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "The first sentence is here.",
+                "The second one follows!",
+                "Is this the third?"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_fall_back_to_words_for_long_sentences() {
+        let chunker = recursive_chunker(3, 1);
+        let text = "one two three four five";
+
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|c| c.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["one two three", "three four five"]
+        );
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.start_pos..chunk.end_pos], chunk.content);
+        }
+    }
+
+    #[test]
+    fn should_share_section_id_between_chunks_of_one_section() {
+        let chunker = recursive_chunker(4, 0);
+        let text = "# Intro\none two three four five six\n# Next\nseven";
+
+        let chunks = chunker.chunk_text(text);
+
+        let sections: Vec<(usize, Vec<String>)> = chunks
+            .iter()
+            .map(|c| (c.section_id, c.heading_path.clone()))
+            .collect();
+        assert!(chunks.len() > 2);
+        let (last, first) = (sections.last().unwrap(), &sections[0]);
+        assert!(sections[..sections.len() - 1]
+            .iter()
+            .all(|(id, path)| *id == 0 && path == &first.1));
+        assert_eq!(last.0, 1);
+    }
+
+    #[test]
+    fn should_leave_heading_path_empty_for_word_window_strategy() {
+        let chunker = TextChunker::new(ChunkConfig::default());
+        let chunks = chunker.chunk_text("# Title\nBody text");
+
+        assert!(chunks[0].heading_path.is_empty());
+    }
+
+    #[test]
+    fn should_report_exact_source_offsets_for_irregular_whitespace() {
+        let chunker = TextChunker::new(ChunkConfig {
+            chunk_size: 2,
+            overlap_size: 0,
+            ..ChunkConfig::default()
+        });
+        let text = "  alpha\t\tbeta\n\n  gamma   delta\n";
+
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].content, "alpha beta");
+        assert_eq!(
+            &text[chunks[0].start_pos..chunks[0].end_pos],
+            "alpha\t\tbeta"
+        );
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 1));
+        assert_eq!(
+            &text[chunks[1].start_pos..chunks[1].end_pos],
+            "gamma   delta"
+        );
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (3, 3));
+    }
+
+    #[test]
+    fn should_report_char_offsets_and_lines_for_multibyte_text() {
+        let chunker = recursive_chunker(3, 0);
+        let text = "héllo wörld\n\nnaïve café olé";
+
+        let chunks = chunker.chunk_text(text);
+
+        assert_eq!(chunks.len(), 2);
+        let last = &chunks[1];
+        assert_eq!(last.content, "naïve café olé");
+        assert_eq!((last.start_char, last.end_char), (13, 27));
+        assert_eq!(
+            text.chars()
+                .skip(last.start_char)
+                .take(last.end_char - last.start_char)
+                .collect::<String>(),
+            last.content
+        );
+        assert_eq!((last.start_line, last.end_line), (3, 3));
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 1));
+    }
 }
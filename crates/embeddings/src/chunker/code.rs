@@ -0,0 +1,498 @@
+use super::{SourceLocator, TextChunk, TextChunker};
+use std::ops::Range;
+use std::path::{Component, Path};
+use tree_sitter::{Node, Parser};
+
+/// Source languages understood by the code chunking strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+}
+
+/// How a syntax node is treated when splitting a file into items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    /// Self-contained item such as a function or struct
+    Leaf,
+    /// Item whose body holds further items (impls, traits, classes)
+    Container,
+    /// Inline module whose body extends the module path
+    Module,
+    /// Comments and attributes that belong to the following item
+    Preamble,
+    /// Anything else (imports, statements), grouped with its neighbours
+    Other,
+}
+
+impl CodeLanguage {
+    /// Detects the language from a file extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("rs") => Some(CodeLanguage::Rust),
+            Some("py") => Some(CodeLanguage::Python),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            CodeLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
+            CodeLanguage::Python => tree_sitter_python::LANGUAGE.into(),
+        }
+    }
+
+    fn separator(self) -> &'static str {
+        match self {
+            CodeLanguage::Rust => "::",
+            CodeLanguage::Python => ".",
+        }
+    }
+
+    /// Module path implied by the file location, e.g. `src/store/models.rs` -> `store::models`
+    fn module_path(self, path: &str) -> String {
+        let path = Path::new(path);
+        let directories: Vec<&str> = path
+            .parent()
+            .into_iter()
+            .flat_map(|parent| parent.components())
+            .filter_map(|component| match component {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        let mut parts = match self {
+            // Rust module paths start below the crate's `src` directory
+            CodeLanguage::Rust => match directories.iter().rposition(|dir| *dir == "src") {
+                Some(src) => directories[src + 1..].to_vec(),
+                None => directories,
+            },
+            CodeLanguage::Python => directories,
+        };
+
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("");
+        match (self, stem) {
+            (CodeLanguage::Rust, "lib" | "main" | "mod") | (CodeLanguage::Python, "__init__") => {}
+            _ => parts.push(stem),
+        }
+        parts.join(self.separator())
+    }
+
+    fn item_kind(self, node: Node) -> ItemKind {
+        match (self, node.kind()) {
+            (
+                CodeLanguage::Rust,
+                "function_item"
+                | "function_signature_item"
+                | "struct_item"
+                | "enum_item"
+                | "union_item"
+                | "type_item"
+                | "const_item"
+                | "static_item"
+                | "macro_definition",
+            ) => ItemKind::Leaf,
+            (CodeLanguage::Rust, "impl_item" | "trait_item") => ItemKind::Container,
+            (CodeLanguage::Rust, "mod_item") if node.child_by_field_name("body").is_some() => {
+                ItemKind::Module
+            }
+            (CodeLanguage::Rust, "attribute_item" | "line_comment" | "block_comment") => {
+                ItemKind::Preamble
+            }
+            (CodeLanguage::Python, "function_definition") => ItemKind::Leaf,
+            (CodeLanguage::Python, "class_definition") => ItemKind::Container,
+            (CodeLanguage::Python, "decorated_definition") => node
+                .child_by_field_name("definition")
+                .map_or(ItemKind::Other, |definition| self.item_kind(definition)),
+            (CodeLanguage::Python, "comment") => ItemKind::Preamble,
+            _ => ItemKind::Other,
+        }
+    }
+
+    /// The item's declaration without its body, whitespace-normalised,
+    /// e.g. `pub fn parse(input: &str) -> Result<Ast>` or `class Parser(Base)`
+    fn signature(self, text: &str, node: Node) -> String {
+        let node = definition(self, node);
+        let declaration = match node.child_by_field_name("body") {
+            Some(body) => &text[node.start_byte()..body.start_byte()],
+            None => text[node.byte_range()].lines().next().unwrap_or(""),
+        };
+        let signature = declaration.split_whitespace().collect::<Vec<_>>().join(" ");
+        signature
+            .trim_end_matches([':', '{', ';', '='])
+            .trim_end()
+            .to_string()
+    }
+}
+
+impl TextChunker {
+    /// Splits source code into one chunk per top-level item, descending into
+    /// oversized impls, traits, classes and inline modules and falling back to
+    /// line windows for oversized items that cannot be split further
+    pub(super) fn chunk_code(
+        &self,
+        text: &str,
+        language: CodeLanguage,
+        file_name: &str,
+    ) -> Vec<TextChunk> {
+        let locator = SourceLocator::new(text);
+        let module = language.module_path(file_name);
+        let mut chunks = Vec::new();
+
+        let mut parser = Parser::new();
+        let tree = parser
+            .set_language(&language.grammar())
+            .ok()
+            .and_then(|_| parser.parse(text, None));
+
+        match tree {
+            Some(tree) => self.chunk_items(
+                &locator,
+                language,
+                tree.root_node(),
+                &module,
+                &[],
+                &mut chunks,
+            ),
+            None => {
+                tracing::warn!(
+                    "Failed to parse {}, falling back to line windows",
+                    file_name
+                );
+                let header = code_header(&module, &[], None);
+                let units = line_units(text, 0..text.len());
+                self.merge_units(&locator, &units, &[], header.as_deref(), &mut chunks);
+            }
+        }
+
+        chunks
+    }
+
+    fn chunk_items(
+        &self,
+        locator: &SourceLocator,
+        language: CodeLanguage,
+        parent: Node,
+        module: &str,
+        scopes: &[String],
+        chunks: &mut Vec<TextChunk>,
+    ) {
+        let text = locator.text;
+        let mut cursor = parent.walk();
+        let children: Vec<Node> = parent.named_children(&mut cursor).collect();
+
+        let mut other: Vec<Range<usize>> = Vec::new();
+        let mut preamble: Vec<Range<usize>> = Vec::new();
+
+        for node in children {
+            let range = line_start(text, node.start_byte())..node.end_byte();
+            let kind = language.item_kind(node);
+            match kind {
+                ItemKind::Preamble => preamble.push(range),
+                ItemKind::Other => {
+                    other.append(&mut preamble);
+                    other.push(range);
+                }
+                ItemKind::Leaf | ItemKind::Container | ItemKind::Module => {
+                    self.flush_other(locator, &mut other, module, scopes, chunks);
+
+                    let start = preamble.first().map_or(range.start, |first| first.start);
+                    preamble.clear();
+                    let item = start..range.end;
+                    let signature = language.signature(text, node);
+
+                    if self.count(&text[item.clone()]) <= self.config.chunk_size {
+                        let header = code_header(module, scopes, Some(&signature));
+                        self.merge_units(locator, &[item], scopes, header.as_deref(), chunks);
+                        continue;
+                    }
+
+                    let body = match kind {
+                        ItemKind::Container | ItemKind::Module => {
+                            definition(language, node).child_by_field_name("body")
+                        }
+                        _ => None,
+                    };
+                    match (kind, body) {
+                        (ItemKind::Module, Some(body)) => {
+                            let name = node
+                                .child_by_field_name("name")
+                                .map_or("", |name| &text[name.byte_range()]);
+                            let nested = [module, name]
+                                .into_iter()
+                                .filter(|part| !part.is_empty())
+                                .collect::<Vec<_>>()
+                                .join(language.separator());
+                            self.chunk_items(locator, language, body, &nested, scopes, chunks);
+                        }
+                        (ItemKind::Container, Some(body)) => {
+                            let mut nested = scopes.to_vec();
+                            nested.push(signature);
+                            self.chunk_items(locator, language, body, module, &nested, chunks);
+                        }
+                        _ => {
+                            let header = code_header(module, scopes, Some(&signature));
+                            let units = line_units(text, item);
+                            self.merge_units(locator, &units, scopes, header.as_deref(), chunks);
+                        }
+                    }
+                }
+            }
+        }
+
+        other.append(&mut preamble);
+        self.flush_other(locator, &mut other, module, scopes, chunks);
+    }
+
+    /// Emits grouped non-item code such as imports, splitting oversized nodes into lines
+    fn flush_other(
+        &self,
+        locator: &SourceLocator,
+        other: &mut Vec<Range<usize>>,
+        module: &str,
+        scopes: &[String],
+        chunks: &mut Vec<TextChunk>,
+    ) {
+        if other.is_empty() {
+            return;
+        }
+
+        let units: Vec<Range<usize>> = other
+            .drain(..)
+            .flat_map(|range| {
+                if self.count(&locator.text[range.clone()]) > self.config.chunk_size {
+                    line_units(locator.text, range)
+                } else {
+                    vec![range]
+                }
+            })
+            .collect();
+        let header = code_header(module, scopes, None);
+        self.merge_units(locator, &units, scopes, header.as_deref(), chunks);
+    }
+}
+
+/// The node carrying the item's fields (unwraps Python decorators)
+fn definition(language: CodeLanguage, node: Node) -> Node {
+    match (language, node.kind()) {
+        (CodeLanguage::Python, "decorated_definition") => {
+            node.child_by_field_name("definition").unwrap_or(node)
+        }
+        _ => node,
+    }
+}
+
+/// Moves `byte` back to the start of its line when only indentation precedes it
+fn line_start(text: &str, byte: usize) -> usize {
+    let start = text[..byte].rfind('\n').map_or(0, |newline| newline + 1);
+    if text[start..byte].trim().is_empty() {
+        start
+    } else {
+        byte
+    }
+}
+
+/// Non-blank lines of `range`, keeping indentation but not trailing whitespace
+fn line_units(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut units = Vec::new();
+    let mut offset = range.start;
+    for line in text[range].split_inclusive('\n') {
+        let content = line.trim_end();
+        if !content.trim().is_empty() {
+            units.push(offset..offset + content.len());
+        }
+        offset += line.len();
+    }
+    units
+}
+
+fn code_header(module: &str, scopes: &[String], signature: Option<&str>) -> Option<String> {
+    let mut lines = Vec::new();
+    if !module.is_empty() {
+        lines.push(format!("Module: {}", module));
+    }
+    if !scopes.is_empty() {
+        lines.push(format!("Scope: {}", scopes.join(" > ")));
+    }
+    if let Some(signature) = signature {
+        lines.push(format!("Item: {}", signature));
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::{ChunkConfig, ChunkStrategy};
+
+    fn code_chunker(chunk_size: usize) -> TextChunker {
+        TextChunker::new(ChunkConfig {
+            chunk_size,
+            overlap_size: 0,
+            ..ChunkConfig::default()
+        })
+    }
+
+    const RUST_SOURCE: &str = r#"use std::fmt;
+use std::collections::HashMap;
+
+/// A parsed configuration value
+#[derive(Debug)]
+pub struct Value {
+    name: String,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+
+    fn len(&self) -> usize {
+        self.name.len()
+    }
+}
+
+pub fn parse(input: &str) -> Value {
+    let name = input.trim().to_string();
+    Value { name }
+}
+"#;
+
+    #[test]
+    fn should_detect_language_from_extension() {
+        assert_eq!(
+            CodeLanguage::from_path("src/lib.rs"),
+            Some(CodeLanguage::Rust)
+        );
+        assert_eq!(
+            CodeLanguage::from_path("pkg/util.py"),
+            Some(CodeLanguage::Python)
+        );
+        assert_eq!(CodeLanguage::from_path("notes.md"), None);
+    }
+
+    #[test]
+    fn should_derive_module_path_from_file_path() {
+        assert_eq!(
+            CodeLanguage::Rust.module_path("crates/store/src/store/models.rs"),
+            "store::models"
+        );
+        assert_eq!(CodeLanguage::Rust.module_path("src/lib.rs"), "");
+        assert_eq!(
+            CodeLanguage::Python.module_path("pkg/sub/__init__.py"),
+            "pkg.sub"
+        );
+    }
+
+    #[test]
+    fn should_split_rust_file_on_top_level_items() {
+        let chunks = code_chunker(500).chunk_file("src/config.rs", RUST_SOURCE);
+
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks[0].content.starts_with("use std::fmt;"));
+        assert_eq!(chunks[0].context_header.as_deref(), Some("Module: config"));
+        assert!(chunks[1]
+            .content
+            .starts_with("/// A parsed configuration value"));
+        assert_eq!(
+            chunks[1].context_header.as_deref(),
+            Some("Module: config\nItem: pub struct Value")
+        );
+        assert!(chunks[2]
+            .content
+            .starts_with("impl fmt::Display for Value {"));
+        assert!(chunks[2].content.ends_with("}"));
+        assert_eq!(
+            chunks[3].context_header.as_deref(),
+            Some("Module: config\nItem: pub fn parse(input: &str) -> Value")
+        );
+        for chunk in &chunks {
+            assert_eq!(&RUST_SOURCE[chunk.start_pos..chunk.end_pos], chunk.content);
+        }
+    }
+
+    #[test]
+    fn should_descend_into_oversized_impl_blocks() {
+        let chunks = code_chunker(20).chunk_file("src/config.rs", RUST_SOURCE);
+
+        let methods: Vec<&TextChunk> = chunks
+            .iter()
+            .filter(|chunk| chunk.heading_path == vec!["impl fmt::Display for Value"])
+            .collect();
+        assert_eq!(methods.len(), 2);
+        assert!(methods[0].content.starts_with("    fn fmt("));
+        assert_eq!(
+            methods[1].context_header.as_deref(),
+            Some(
+                "Module: config\nScope: impl fmt::Display for Value\nItem: fn len(&self) -> usize"
+            )
+        );
+    }
+
+    #[test]
+    fn should_fall_back_to_line_windows_for_oversized_items() {
+        let chunks = code_chunker(8).chunk_file("src/config.rs", RUST_SOURCE);
+
+        let parse_chunks: Vec<&TextChunk> = chunks
+            .iter()
+            .filter(|chunk| {
+                chunk
+                    .context_header
+                    .as_deref()
+                    .is_some_and(|header| header.ends_with("pub fn parse(input: &str) -> Value"))
+            })
+            .collect();
+        assert!(parse_chunks.len() > 1);
+        for chunk in parse_chunks {
+            assert_eq!(&RUST_SOURCE[chunk.start_pos..chunk.end_pos], chunk.content);
+            assert!(!chunk.content.ends_with('\n'));
+        }
+    }
+
+    #[test]
+    fn should_split_python_file_on_classes_and_functions() {
+        let source = "import os\n\n\nclass Loader(Base):\n    \"\"\"Loads files.\"\"\"\n\n    def load(self, path):\n        return open(path).read()\n\n\n@cached\ndef helper(x: int) -> int:\n    return x * 2\n";
+        let chunks = code_chunker(500).chunk_file("pkg/loader.py", source);
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].content, "import os");
+        assert_eq!(
+            chunks[1].context_header.as_deref(),
+            Some("Module: pkg.loader\nItem: class Loader(Base)")
+        );
+        assert!(chunks[2].content.starts_with("@cached\ndef helper"));
+        assert_eq!(
+            chunks[2].context_header.as_deref(),
+            Some("Module: pkg.loader\nItem: def helper(x: int) -> int")
+        );
+    }
+
+    #[test]
+    fn should_use_configured_strategy_for_non_code_files() {
+        let chunker = code_chunker(500);
+
+        let chunks = chunker.chunk_file("notes.txt", "fn main() {}");
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].context_header, None);
+    }
+
+    #[test]
+    fn should_include_context_header_in_embedding_text() {
+        let chunker = TextChunker::new(ChunkConfig {
+            strategy: ChunkStrategy::Code(CodeLanguage::Rust),
+            ..ChunkConfig::default()
+        });
+
+        let chunks = chunker.chunk_text("fn main() {}\n");
+
+        assert_eq!(
+            chunks[0].embedding_text(),
+            "Item: fn main()\n\nfn main() {}"
+        );
+    }
+}
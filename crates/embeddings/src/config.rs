@@ -6,6 +6,45 @@ pub struct EmbeddingConfig {
     pub model: Option<String>,
     pub aws_region: Option<String>,
     pub dimensions: Option<usize>,
+    /// Embed a test string at startup and verify the provider's reported dimension
+    #[serde(default)]
+    pub probe_dimension: bool,
+    /// Providers tried in order when the primary fails with a retryable error
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failover: Vec<EmbeddingConfig>,
+    /// Base URL of an OpenAI-compatible embeddings server
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Name of the environment variable holding the API key for HTTP providers
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Maximum number of texts sent per embeddings request
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// WordPiece `vocab.txt` used to count tokens when chunking; a per-model
+    /// characters-per-token heuristic is used when unset
+    #[serde(default)]
+    pub tokenizer_vocab: Option<String>,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            provider: "fallback".to_string(),
+            model: None,
+            aws_region: None,
+            dimensions: None,
+            probe_dimension: false,
+            failover: Vec::new(),
+            base_url: None,
+            api_key_env: None,
+            batch_size: None,
+            max_retries: None,
+            tokenizer_vocab: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -19,6 +58,8 @@ mod tests {
             model: Some("cohere.embed-multilingual-v3".to_string()),
             aws_region: Some("us-east-1".to_string()),
             dimensions: Some(1024),
+            probe_dimension: true,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -33,10 +74,40 @@ mod tests {
             model: None,
             aws_region: None,
             dimensions: None,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
         let deserialized: EmbeddingConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(config, deserialized);
     }
+
+    #[test]
+    fn should_default_probe_dimension_to_false_when_missing() {
+        let json =
+            r#"{"provider":"bedrock-titan","model":null,"aws_region":null,"dimensions":512}"#;
+        let config: EmbeddingConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.dimensions, Some(512));
+        assert!(!config.probe_dimension);
+    }
+
+    #[test]
+    fn should_deserialize_failover_chain() {
+        let json = r#"{
+            "provider": "bedrock-cohere",
+            "model": null,
+            "aws_region": "eu-central-1",
+            "dimensions": null,
+            "failover": [
+                {"provider": "bedrock-titan", "model": null, "aws_region": "eu-central-1", "dimensions": 1024}
+            ]
+        }"#;
+        let config: EmbeddingConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.failover.len(), 1);
+        assert_eq!(config.failover[0].provider, "bedrock-titan");
+        assert_eq!(config.failover[0].dimensions, Some(1024));
+        assert!(config.failover[0].failover.is_empty());
+    }
 }
@@ -0,0 +1,199 @@
+use anyhow::Result;
+
+/// Character n-gram lengths hashed in addition to whole words
+const CHAR_NGRAM_SIZES: [usize; 2] = [3, 4];
+/// Relative weight of character n-gram features compared to word features
+const CHAR_NGRAM_WEIGHT: f32 = 0.5;
+/// Relative weight of word bigram features compared to word features
+const WORD_BIGRAM_WEIGHT: f32 = 0.75;
+
+/// Offline embeddings provider based on feature hashing of word and character n-grams.
+/// Features are TF-weighted (sublinear), hashed into a fixed number of signed buckets
+/// and L2-normalised, so cosine similarity reflects lexical overlap between texts.
+/// Deterministic across runs and platforms; needs no model download or network access.
+pub struct LexicalEmbeddingProvider {
+    embedding_dim: usize,
+}
+
+impl LexicalEmbeddingProvider {
+    pub fn new(embedding_dim: usize) -> Self {
+        Self { embedding_dim }
+    }
+
+    /// Creates embeddings provider with standard embedding dimension (1024)
+    pub fn with_standard_dimension() -> Self {
+        Self::new(1024)
+    }
+
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+
+    /// Get the embedding dimension used by this provider
+    pub fn embedding_dimension(&self) -> usize {
+        self.embedding_dim
+    }
+
+    fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut embedding = vec![0.0f32; self.embedding_dim];
+        if self.embedding_dim == 0 {
+            return embedding;
+        }
+
+        let mut features: std::collections::HashMap<String, (f32, u32)> =
+            std::collections::HashMap::new();
+        let mut add_feature = |feature: String, weight: f32| {
+            features.entry(feature).or_insert((weight, 0)).1 += 1;
+        };
+
+        let words = tokenize(text);
+        for word in &words {
+            add_feature(format!("w:{}", word), 1.0);
+
+            let padded: Vec<char> = format!("<{}>", word).chars().collect();
+            for size in CHAR_NGRAM_SIZES {
+                for ngram in padded.windows(size) {
+                    add_feature(
+                        format!("c:{}", ngram.iter().collect::<String>()),
+                        CHAR_NGRAM_WEIGHT,
+                    );
+                }
+            }
+        }
+        for pair in words.windows(2) {
+            add_feature(format!("b:{} {}", pair[0], pair[1]), WORD_BIGRAM_WEIGHT);
+        }
+
+        for (feature, (weight, count)) in features {
+            let hash = fnv1a(feature.as_bytes());
+            let bucket = (hash % self.embedding_dim as u64) as usize;
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            // Sublinear term frequency keeps repeated words from dominating
+            let tf = 1.0 + (count as f32).ln();
+            embedding[bucket] += sign * weight * tf;
+        }
+
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for value in &mut embedding {
+                *value /= norm;
+            }
+        }
+
+        embedding
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// 64-bit FNV-1a, used instead of `DefaultHasher` so vectors are stable across Rust versions
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[tokio::test]
+    async fn should_create_lexical_provider_with_standard_dimension() {
+        let provider = LexicalEmbeddingProvider::with_standard_dimension();
+        assert_eq!(provider.embedding_dimension(), 1024);
+    }
+
+    #[tokio::test]
+    async fn should_return_empty_embeddings_for_empty_input() {
+        let provider = LexicalEmbeddingProvider::new(256);
+        let result = provider.embed(vec![]).await.unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_return_zero_vector_for_text_without_words() {
+        let provider = LexicalEmbeddingProvider::new(64);
+        let result = provider.embed(vec!["  ... !! ".to_string()]).await.unwrap();
+
+        assert_eq!(result[0].len(), 64);
+        assert!(result[0].iter().all(|&x| x == 0.0));
+    }
+
+    #[tokio::test]
+    async fn should_produce_unit_length_vectors() {
+        let provider = LexicalEmbeddingProvider::new(512);
+        let result = provider
+            .embed(vec!["Employees accrue vacation days monthly".to_string()])
+            .await
+            .unwrap();
+
+        let norm = result[0].iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn should_be_deterministic() {
+        let provider = LexicalEmbeddingProvider::new(128);
+        let texts = vec!["same text".to_string()];
+
+        let result1 = provider.embed(texts.clone()).await.unwrap();
+        let result2 = provider.embed(texts).await.unwrap();
+
+        assert_eq!(result1, result2);
+    }
+
+    #[tokio::test]
+    async fn should_score_related_texts_higher_than_unrelated_texts() {
+        let provider = LexicalEmbeddingProvider::with_standard_dimension();
+        let result = provider
+            .embed(vec![
+                "How many vacation days do employees get?".to_string(),
+                "Employees receive 25 vacation days per year.".to_string(),
+                "Rust code must pass clippy before merging.".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        let related = cosine(&result[0], &result[1]);
+        let unrelated = cosine(&result[0], &result[2]);
+        assert!(
+            related > unrelated + 0.1,
+            "related={} unrelated={}",
+            related,
+            unrelated
+        );
+    }
+
+    #[tokio::test]
+    async fn should_match_word_variants_through_character_ngrams() {
+        let provider = LexicalEmbeddingProvider::with_standard_dimension();
+        let result = provider
+            .embed(vec![
+                "onboarding".to_string(),
+                "onboard".to_string(),
+                "expenses".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        assert!(cosine(&result[0], &result[1]) > cosine(&result[0], &result[2]));
+    }
+
+    #[test]
+    fn should_hash_with_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
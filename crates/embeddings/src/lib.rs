@@ -1,19 +1,33 @@
 pub mod bedrock_cohere;
 pub mod bedrock_common;
+pub mod bedrock_rerank;
 pub mod bedrock_titan;
+pub mod chain;
 pub mod chunker;
 pub mod config;
 pub mod fallback;
+pub mod lexical;
+pub mod openai_compatible;
+pub mod tokenizer;
 
 pub use bedrock_cohere::{BedrockCohereClient, BedrockCohereConfig};
+pub use bedrock_rerank::{BedrockRerankClient, BedrockRerankConfig, RerankResult};
 pub use bedrock_titan::{BedrockTitanClient, BedrockTitanConfig};
-pub use chunker::{ChunkConfig, TextChunk, TextChunker};
+pub use chain::{is_retryable_error, transient, ProviderChain, TransientError};
+pub use chunker::{ChunkConfig, ChunkStrategy, CodeLanguage, TextChunk, TextChunker};
 pub use config::EmbeddingConfig;
 pub use fallback::FallbackEmbeddingProvider;
+pub use lexical::LexicalEmbeddingProvider;
+pub use openai_compatible::{OpenAiCompatibleClient, OpenAiCompatibleConfig};
+pub use tokenizer::{
+    CharsPerTokenCounter, TokenCounter, WhitespaceTokenCounter, WordPieceTokenCounter,
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use tracing::info;
 
-type EmbedFuture<'a> =
+pub(crate) type EmbedFuture<'a> =
     std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<Vec<f32>>>> + Send + 'a>>;
 
 pub trait EmbeddingProvider: Send + Sync {
@@ -26,7 +40,7 @@ impl EmbeddingProvider for BedrockCohereClient {
         Box::pin(self.embed(texts))
     }
     fn dimension(&self) -> usize {
-        1024
+        self.dimension()
     }
 }
 
@@ -35,7 +49,7 @@ impl EmbeddingProvider for BedrockTitanClient {
         Box::pin(self.embed(texts))
     }
     fn dimension(&self) -> usize {
-        1024
+        self.dimension()
     }
 }
 
@@ -48,9 +62,119 @@ impl EmbeddingProvider for FallbackEmbeddingProvider {
     }
 }
 
+impl EmbeddingProvider for OpenAiCompatibleClient {
+    fn embed(&self, texts: Vec<String>) -> EmbedFuture<'_> {
+        Box::pin(self.embed(texts))
+    }
+    fn dimension(&self) -> usize {
+        self.dimension()
+    }
+}
+
+impl EmbeddingProvider for LexicalEmbeddingProvider {
+    fn embed(&self, texts: Vec<String>) -> EmbedFuture<'_> {
+        Box::pin(self.embed(texts))
+    }
+    fn dimension(&self) -> usize {
+        self.embedding_dimension()
+    }
+}
+
+/// Provider names accepted in `EmbeddingConfig.provider`
+pub const SUPPORTED_PROVIDERS: [&str; 5] = [
+    "bedrock-cohere",
+    "bedrock-titan",
+    "openai-compatible",
+    "lexical",
+    "fallback",
+];
+
+/// Embeds a short test string and checks that the returned vector has the
+/// dimension the provider reports. Returns the observed dimension.
+pub async fn probe_dimension(provider: &dyn EmbeddingProvider) -> Result<usize> {
+    let embeddings = provider
+        .embed(vec!["dimension probe".to_string()])
+        .await
+        .context("Embedding dimension probe failed")?;
+    let observed = embeddings
+        .first()
+        .map(|embedding| embedding.len())
+        .ok_or_else(|| anyhow::anyhow!("Embedding dimension probe returned no embeddings"))?;
+
+    if observed != provider.dimension() {
+        anyhow::bail!(
+            "Embedding provider reports {} dimensions but produced {}",
+            provider.dimension(),
+            observed
+        );
+    }
+
+    Ok(observed)
+}
+
+/// Builds the token counter used to size chunks for the configured embedding model.
+/// A WordPiece vocabulary file takes precedence over the per-model character heuristic.
+pub fn create_token_counter(cfg: &EmbeddingConfig) -> Result<Arc<dyn TokenCounter>> {
+    if let Some(vocab_path) = &cfg.tokenizer_vocab {
+        let counter =
+            WordPieceTokenCounter::from_vocab_file(std::path::Path::new(vocab_path), true)?;
+        info!(
+            "Loaded WordPiece vocabulary with {} tokens from {}",
+            counter.vocab_size(),
+            vocab_path
+        );
+        return Ok(Arc::new(counter));
+    }
+
+    let model = cfg.model.as_deref().unwrap_or(match cfg.provider.as_str() {
+        "bedrock-cohere" => "cohere.embed-multilingual-v3",
+        "bedrock-titan" => "amazon.titan-embed-text-v2:0",
+        other => other,
+    });
+    Ok(Arc::new(CharsPerTokenCounter::for_model(model)))
+}
+
 pub async fn create_embedding_provider(
     cfg: &EmbeddingConfig,
 ) -> Result<Box<dyn EmbeddingProvider>> {
+    let primary = build_embedding_provider(cfg).await?;
+
+    let provider: Box<dyn EmbeddingProvider> = if cfg.failover.is_empty() {
+        primary
+    } else {
+        let mut providers = vec![(cfg.provider.clone(), primary)];
+        for failover_cfg in &cfg.failover {
+            if !failover_cfg.failover.is_empty() {
+                anyhow::bail!(
+                    "Nested failover is not supported for embedding provider '{}'",
+                    failover_cfg.provider
+                );
+            }
+            providers.push((
+                failover_cfg.provider.clone(),
+                build_embedding_provider(failover_cfg).await?,
+            ));
+        }
+        let chain = ProviderChain::new(providers)?;
+        info!(
+            "Embedding provider chain configured: {}",
+            chain.provider_names().join(" -> ")
+        );
+        Box::new(chain)
+    };
+
+    if cfg.probe_dimension {
+        let dimension = probe_dimension(provider.as_ref()).await?;
+        info!(
+            "Embedding provider '{}' verified to produce {} dimensions",
+            cfg.provider, dimension
+        );
+    }
+
+    Ok(provider)
+}
+
+async fn build_embedding_provider(cfg: &EmbeddingConfig) -> Result<Box<dyn EmbeddingProvider>> {
     match cfg.provider.as_str() {
         "bedrock-cohere" => {
             let model_id = cfg
@@ -87,8 +211,195 @@ pub async fn create_embedding_provider(
             }
             Ok(Box::new(BedrockTitanClient::new(br_cfg).await?))
         }
-        _ => Ok(Box::new(
-            FallbackEmbeddingProvider::with_standard_dimension(),
-        )),
+        "openai-compatible" => {
+            let defaults = OpenAiCompatibleConfig::default();
+            let api_key_env = cfg
+                .api_key_env
+                .clone()
+                .unwrap_or_else(|| "OPENAI_API_KEY".to_string());
+            let http_cfg = OpenAiCompatibleConfig {
+                base_url: cfg.base_url.clone().unwrap_or(defaults.base_url),
+                model: cfg.model.clone().unwrap_or(defaults.model),
+                api_key: std::env::var(&api_key_env).ok(),
+                dimensions: cfg.dimensions,
+                batch_size: cfg.batch_size.unwrap_or(defaults.batch_size),
+                max_retries: cfg.max_retries.unwrap_or(defaults.max_retries),
+                ..defaults
+            };
+            Ok(Box::new(OpenAiCompatibleClient::new(http_cfg).await?))
+        }
+        // Local setups get the offline lexical provider so retrieval still ranks sensibly
+        "lexical" | "fallback" => match cfg.dimensions {
+            Some(dim) => Ok(Box::new(LexicalEmbeddingProvider::new(dim))),
+            None => Ok(Box::new(LexicalEmbeddingProvider::with_standard_dimension())),
+        },
+        unknown => anyhow::bail!(
+            "Unknown embedding provider '{}'. Supported providers: {}",
+            unknown,
+            SUPPORTED_PROVIDERS.join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MisreportingProvider;
+
+    impl EmbeddingProvider for MisreportingProvider {
+        fn embed(&self, texts: Vec<String>) -> EmbedFuture<'_> {
+            Box::pin(async move { Ok(texts.iter().map(|_| vec![0.1; 256]).collect()) })
+        }
+        fn dimension(&self) -> usize {
+            1024
+        }
+    }
+
+    #[tokio::test]
+    async fn should_probe_dimension_of_consistent_provider() {
+        let provider = FallbackEmbeddingProvider::new(512);
+        let dimension = probe_dimension(&provider).await.unwrap();
+
+        assert_eq!(dimension, 512);
+    }
+
+    #[tokio::test]
+    async fn should_fail_probe_when_reported_dimension_is_wrong() {
+        let result = probe_dimension(&MisreportingProvider).await;
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("reports 1024 dimensions but produced 256"));
+    }
+
+    #[tokio::test]
+    async fn should_probe_dimension_when_enabled_in_config() {
+        let cfg = EmbeddingConfig {
+            provider: "fallback".to_string(),
+            model: None,
+            aws_region: None,
+            dimensions: None,
+            probe_dimension: true,
+            ..Default::default()
+        };
+
+        let provider = create_embedding_provider(&cfg).await.unwrap();
+        assert_eq!(provider.dimension(), 1024);
+    }
+
+    #[tokio::test]
+    async fn should_reject_unknown_provider_name() {
+        let cfg = EmbeddingConfig {
+            provider: "cohere-typo".to_string(),
+            model: None,
+            aws_region: None,
+            dimensions: None,
+            ..Default::default()
+        };
+
+        let error = create_embedding_provider(&cfg).await.err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("Unknown embedding provider 'cohere-typo'"));
+    }
+
+    #[tokio::test]
+    async fn should_build_provider_chain_from_failover_config() {
+        let secondary = EmbeddingConfig {
+            provider: "fallback".to_string(),
+            model: None,
+            aws_region: None,
+            dimensions: Some(256),
+            ..Default::default()
+        };
+        let cfg = EmbeddingConfig {
+            provider: "fallback".to_string(),
+            model: None,
+            aws_region: None,
+            dimensions: Some(256),
+            failover: vec![secondary],
+            ..Default::default()
+        };
+
+        let provider = create_embedding_provider(&cfg).await.unwrap();
+        assert_eq!(provider.dimension(), 256);
+    }
+
+    #[tokio::test]
+    async fn should_reject_failover_chain_with_different_dimensions() {
+        let secondary = EmbeddingConfig {
+            provider: "fallback".to_string(),
+            model: None,
+            aws_region: None,
+            dimensions: Some(512),
+            ..Default::default()
+        };
+        let cfg = EmbeddingConfig {
+            provider: "fallback".to_string(),
+            model: None,
+            aws_region: None,
+            dimensions: Some(1024),
+            failover: vec![secondary],
+            ..Default::default()
+        };
+
+        let error = create_embedding_provider(&cfg).await.err().unwrap();
+        assert!(error.to_string().contains("dimension mismatch"));
+    }
+
+    #[tokio::test]
+    async fn should_create_openai_compatible_provider_from_config() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"index": 0, "embedding": [0.1, 0.2, 0.3, 0.4]}]
+            })))
+            .mount(&server)
+            .await;
+
+        let cfg = EmbeddingConfig {
+            provider: "openai-compatible".to_string(),
+            model: Some("bge-small".to_string()),
+            base_url: Some(server.uri()),
+            dimensions: Some(4),
+            ..Default::default()
+        };
+
+        let provider = create_embedding_provider(&cfg).await.unwrap();
+        let result = provider.embed(vec!["hello".to_string()]).await.unwrap();
+
+        assert_eq!(provider.dimension(), 4);
+        assert_eq!(result[0].len(), 4);
+    }
+
+    #[test]
+    fn should_create_heuristic_token_counter_for_provider_default_model() {
+        let cfg = EmbeddingConfig {
+            provider: "bedrock-cohere".to_string(),
+            ..Default::default()
+        };
+
+        let counter = create_token_counter(&cfg).unwrap();
+        // multilingual heuristic: 3 characters per token
+        assert_eq!(counter.count_tokens("abcdef"), 2);
+    }
+
+    #[test]
+    fn should_create_wordpiece_token_counter_from_vocab_file() {
+        use std::io::Write;
+
+        let mut vocab = tempfile::NamedTempFile::new().unwrap();
+        writeln!(vocab, "submit\nexpense\n##s").unwrap();
+        let cfg = EmbeddingConfig {
+            tokenizer_vocab: Some(vocab.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        let counter = create_token_counter(&cfg).unwrap();
+        assert_eq!(counter.count_tokens("Submit expenses"), 3);
     }
 }
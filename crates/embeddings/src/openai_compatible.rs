@@ -0,0 +1,444 @@
+use crate::chain::transient;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone)]
+pub struct OpenAiCompatibleConfig {
+    /// Base URL of the server, e.g. `http://localhost:8080`; `/v1/embeddings` is appended
+    pub base_url: String,
+    pub model: String,
+    /// Bearer token sent in the `Authorization` header, if any
+    pub api_key: Option<String>,
+    /// Requested output dimension; discovered with a probe request when unset
+    pub dimensions: Option<usize>,
+    pub batch_size: usize,
+    pub timeout_secs: u64,
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+}
+
+impl Default for OpenAiCompatibleConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:8080".to_string(),
+            model: "text-embedding-3-small".to_string(),
+            api_key: None,
+            dimensions: None,
+            batch_size: 32,
+            timeout_secs: 30,
+            max_retries: 3,
+            initial_backoff_ms: 500,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    index: usize,
+}
+
+/// Client for any server exposing the OpenAI `/v1/embeddings` API
+/// (text-embeddings-inference, Ollama, vLLM, LiteLLM gateways, ...)
+pub struct OpenAiCompatibleClient {
+    config: OpenAiCompatibleConfig,
+    client: reqwest::Client,
+    dimension: usize,
+}
+
+impl OpenAiCompatibleClient {
+    pub async fn new(config: OpenAiCompatibleConfig) -> Result<Self> {
+        if config.base_url.is_empty() {
+            anyhow::bail!("OpenAI-compatible embeddings base URL cannot be empty");
+        }
+        if config.batch_size == 0 {
+            anyhow::bail!("OpenAI-compatible embeddings batch size must be greater than zero");
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .context("Failed to build HTTP client")?;
+
+        let mut provider = Self {
+            dimension: config.dimensions.unwrap_or(0),
+            config,
+            client,
+        };
+
+        if provider.config.dimensions.is_none() {
+            let probe = provider
+                .embed(vec!["dimension probe".to_string()])
+                .await
+                .context("Failed to discover embedding dimension from server")?;
+            provider.dimension = match probe.first().map(Vec::len) {
+                Some(dimension) if dimension > 0 => dimension,
+                _ => anyhow::bail!(
+                    "Embedding server at {} returned no vector for model '{}'; \
+                     set `dimensions` in the embedding config",
+                    provider.config.base_url,
+                    provider.config.model
+                ),
+            };
+            info!(
+                "Discovered {} dimensions for model '{}' at {}",
+                provider.dimension, provider.config.model, provider.config.base_url
+            );
+        }
+
+        Ok(provider)
+    }
+
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let mut results = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.config.batch_size) {
+            results.extend(self.embed_batch_with_retries(batch).await?);
+        }
+
+        // Servers such as Ollama ignore the requested `dimensions`; catch that here
+        // rather than as a vector store error. Unchecked while probing (dimension 0).
+        if self.dimension > 0 {
+            if let Some(embedding) = results.iter().find(|e| e.len() != self.dimension) {
+                anyhow::bail!(
+                    "Embedding server at {} returned {}-dimensional vectors for model '{}' \
+                     but {} dimensions are configured; the server may ignore `dimensions`, \
+                     so set it to the model's native dimension or leave it unset",
+                    self.config.base_url,
+                    embedding.len(),
+                    self.config.model,
+                    self.dimension
+                );
+            }
+        }
+        Ok(results)
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn endpoint(&self) -> String {
+        format!(
+            "{}/v1/embeddings",
+            self.config.base_url.trim_end_matches('/')
+        )
+    }
+
+    async fn embed_batch_with_retries(&self, batch: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut attempt = 0;
+        loop {
+            match self.try_embed_batch(batch).await {
+                Ok(embeddings) => return Ok(embeddings),
+                Err(RequestError::Permanent(e)) => return Err(e),
+                Err(RequestError::Transient(e)) => {
+                    if attempt >= self.config.max_retries {
+                        error!("All embedding attempts failed. Final error: {}", e);
+                        return Err(transient(e));
+                    }
+                    warn!("Embedding attempt {} failed: {}", attempt + 1, e);
+                    let delay = self.config.initial_backoff_ms * 2_u64.pow(attempt);
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn try_embed_batch(&self, batch: &[String]) -> Result<Vec<Vec<f32>>, RequestError> {
+        let request = EmbeddingsRequest {
+            model: &self.config.model,
+            input: batch,
+            dimensions: self.config.dimensions,
+        };
+
+        let mut builder = self.client.post(self.endpoint()).json(&request);
+        if let Some(api_key) = &self.config.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            RequestError::Transient(anyhow::anyhow!(
+                "OpenAI-compatible embeddings request failed (connection or timeout): {}",
+                e
+            ))
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let error = anyhow::anyhow!(
+                "OpenAI-compatible embeddings request failed with status {}: {}",
+                status,
+                body
+            );
+            return Err(if status.as_u16() == 429 || status.is_server_error() {
+                RequestError::Transient(error)
+            } else {
+                RequestError::Permanent(error)
+            });
+        }
+
+        let mut parsed: EmbeddingsResponse = response.json().await.map_err(|e| {
+            RequestError::Permanent(anyhow::anyhow!(
+                "Failed to parse OpenAI-compatible embeddings response: {}",
+                e
+            ))
+        })?;
+
+        if parsed.data.len() != batch.len() {
+            return Err(RequestError::Permanent(anyhow::anyhow!(
+                "OpenAI-compatible embeddings response has {} embeddings for {} inputs",
+                parsed.data.len(),
+                batch.len()
+            )));
+        }
+
+        parsed.data.sort_by_key(|data| data.index);
+        Ok(parsed.data.into_iter().map(|data| data.embedding).collect())
+    }
+}
+
+enum RequestError {
+    Transient(anyhow::Error),
+    Permanent(anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    fn test_config(server: &MockServer) -> OpenAiCompatibleConfig {
+        OpenAiCompatibleConfig {
+            base_url: server.uri(),
+            model: "test-model".to_string(),
+            dimensions: Some(3),
+            initial_backoff_ms: 1,
+            ..OpenAiCompatibleConfig::default()
+        }
+    }
+
+    /// Responds with one 3-dimensional embedding per input, in reverse index order
+    fn echo_embeddings(request: &Request) -> ResponseTemplate {
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let inputs = body["input"].as_array().unwrap();
+        let data: Vec<_> = inputs
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, _)| json!({"object": "embedding", "index": index, "embedding": [index as f32, 0.5, 1.0]}))
+            .collect();
+        ResponseTemplate::new(200).set_body_json(json!({"object": "list", "data": data}))
+    }
+
+    #[tokio::test]
+    async fn should_embed_texts_in_input_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .and(body_partial_json(
+                json!({"model": "test-model", "dimensions": 3}),
+            ))
+            .respond_with(echo_embeddings)
+            .mount(&server)
+            .await;
+
+        let client = OpenAiCompatibleClient::new(test_config(&server))
+            .await
+            .unwrap();
+        let result = client
+            .embed(vec!["first".to_string(), "second".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(result, vec![vec![0.0, 0.5, 1.0], vec![1.0, 0.5, 1.0]]);
+        assert_eq!(client.dimension(), 3);
+    }
+
+    #[tokio::test]
+    async fn should_split_requests_into_batches() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .respond_with(echo_embeddings)
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let config = OpenAiCompatibleConfig {
+            batch_size: 2,
+            ..test_config(&server)
+        };
+        let client = OpenAiCompatibleClient::new(config).await.unwrap();
+        let texts = (0..5).map(|i| format!("text {}", i)).collect();
+        let result = client.embed(texts).await.unwrap();
+
+        assert_eq!(result.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn should_send_api_key_as_bearer_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("authorization", "Bearer secret-key"))
+            .respond_with(echo_embeddings)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = OpenAiCompatibleConfig {
+            api_key: Some("secret-key".to_string()),
+            ..test_config(&server)
+        };
+        let client = OpenAiCompatibleClient::new(config).await.unwrap();
+
+        assert!(client.embed(vec!["hello".to_string()]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_discover_dimension_when_not_configured() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(echo_embeddings)
+            .mount(&server)
+            .await;
+
+        let config = OpenAiCompatibleConfig {
+            dimensions: None,
+            ..test_config(&server)
+        };
+        let client = OpenAiCompatibleClient::new(config).await.unwrap();
+
+        assert_eq!(client.dimension(), 3);
+    }
+
+    #[tokio::test]
+    async fn should_fail_when_dimension_probe_returns_empty_vector() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "list",
+                "data": [{"object": "embedding", "index": 0, "embedding": []}]
+            })))
+            .mount(&server)
+            .await;
+
+        let config = OpenAiCompatibleConfig {
+            dimensions: None,
+            ..test_config(&server)
+        };
+        let error = OpenAiCompatibleClient::new(config).await.err().unwrap();
+
+        assert!(error.to_string().contains("returned no vector"));
+    }
+
+    #[tokio::test]
+    async fn should_retry_on_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(echo_embeddings)
+            .mount(&server)
+            .await;
+
+        let client = OpenAiCompatibleClient::new(test_config(&server))
+            .await
+            .unwrap();
+        let result = client.embed(vec!["hello".to_string()]).await.unwrap();
+
+        assert_eq!(result.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_give_up_after_max_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let config = OpenAiCompatibleConfig {
+            max_retries: 2,
+            ..test_config(&server)
+        };
+        let client = OpenAiCompatibleClient::new(config).await.unwrap();
+        let error = client.embed(vec!["hello".to_string()]).await.unwrap_err();
+
+        assert!(error.to_string().contains("429 Too Many Requests"));
+        assert!(crate::is_retryable_error(&error));
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_on_client_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string("unknown model, check connection"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = OpenAiCompatibleClient::new(test_config(&server))
+            .await
+            .unwrap();
+        let error = client.embed(vec!["hello".to_string()]).await.unwrap_err();
+
+        assert!(error.to_string().contains("unknown model"));
+        assert!(!crate::is_retryable_error(&error));
+    }
+
+    #[tokio::test]
+    async fn should_reject_vectors_not_matching_configured_dimensions() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(echo_embeddings)
+            .mount(&server)
+            .await;
+
+        let config = OpenAiCompatibleConfig {
+            dimensions: Some(256),
+            ..test_config(&server)
+        };
+        let client = OpenAiCompatibleClient::new(config).await.unwrap();
+        let error = client.embed(vec!["hello".to_string()]).await.unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("returned 3-dimensional vectors for model 'test-model' but 256"));
+        assert!(!crate::is_retryable_error(&error));
+    }
+
+    #[tokio::test]
+    async fn should_reject_zero_batch_size() {
+        let config = OpenAiCompatibleConfig {
+            batch_size: 0,
+            dimensions: Some(3),
+            ..OpenAiCompatibleConfig::default()
+        };
+
+        assert!(OpenAiCompatibleClient::new(config).await.is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Counts tokens the way an embedding model would, so chunks can be sized
+/// against the model's real input limit.
+pub trait TokenCounter: Send + Sync + std::fmt::Debug {
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Treats every whitespace-separated word as one token
+#[derive(Debug, Clone, Default)]
+pub struct WhitespaceTokenCounter;
+
+impl TokenCounter for WhitespaceTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+}
+
+/// Approximates subword tokenizers with an average number of characters per token
+#[derive(Debug, Clone)]
+pub struct CharsPerTokenCounter {
+    chars_per_token: f32,
+}
+
+impl CharsPerTokenCounter {
+    pub fn new(chars_per_token: f32) -> Self {
+        Self {
+            chars_per_token: chars_per_token.max(1.0),
+        }
+    }
+
+    /// Heuristic ratio for the given embedding model id.
+    /// Multilingual vocabularies split text more aggressively, so they get a lower ratio.
+    pub fn for_model(model_id: &str) -> Self {
+        let chars_per_token = if model_id.contains("multilingual") {
+            3.0
+        } else if model_id.starts_with("cohere.") {
+            3.5
+        } else if model_id.starts_with("amazon.titan") {
+            4.5
+        } else {
+            4.0
+        };
+        Self::new(chars_per_token)
+    }
+
+    pub fn chars_per_token(&self) -> f32 {
+        self.chars_per_token
+    }
+}
+
+impl TokenCounter for CharsPerTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        let chars = text.chars().filter(|c| !c.is_whitespace()).count();
+        (chars as f32 / self.chars_per_token).ceil() as usize
+    }
+}
+
+/// Greedy longest-match-first WordPiece tokenizer loaded from a BERT-style `vocab.txt`
+#[derive(Debug, Clone)]
+pub struct WordPieceTokenCounter {
+    vocab: HashSet<String>,
+    lowercase: bool,
+    max_input_chars_per_word: usize,
+}
+
+impl WordPieceTokenCounter {
+    pub fn from_vocab<I, S>(tokens: I, lowercase: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            vocab: tokens.into_iter().map(Into::into).collect(),
+            lowercase,
+            max_input_chars_per_word: 100,
+        }
+    }
+
+    /// Loads a vocabulary file with one token per line
+    pub fn from_vocab_file(path: &Path, lowercase: bool) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tokenizer vocabulary: {}", path.display()))?;
+        let tokens = content
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty());
+        Ok(Self::from_vocab(tokens, lowercase))
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.vocab.len()
+    }
+
+    fn count_word_pieces(&self, word: &str) -> usize {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() > self.max_input_chars_per_word {
+            return 1; // [UNK]
+        }
+
+        let mut pieces = 0;
+        let mut start = 0;
+        while start < chars.len() {
+            let mut end = chars.len();
+            let mut matched = false;
+            while start < end {
+                let candidate: String = chars[start..end].iter().collect();
+                let candidate = if start > 0 {
+                    format!("##{}", candidate)
+                } else {
+                    candidate
+                };
+                if self.vocab.contains(&candidate) {
+                    matched = true;
+                    break;
+                }
+                end -= 1;
+            }
+            if !matched {
+                return 1; // the whole word becomes [UNK]
+            }
+            pieces += 1;
+            start = end;
+        }
+        pieces
+    }
+}
+
+impl TokenCounter for WordPieceTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        let text = if self.lowercase {
+            text.to_lowercase()
+        } else {
+            text.to_string()
+        };
+
+        // BERT basic tokenization: split on whitespace, then isolate punctuation
+        let mut count = 0;
+        for word in text.split_whitespace() {
+            let mut current = String::new();
+            for c in word.chars() {
+                if c.is_ascii_punctuation() {
+                    if !current.is_empty() {
+                        count += self.count_word_pieces(&current);
+                        current.clear();
+                    }
+                    count += 1;
+                } else {
+                    current.push(c);
+                }
+            }
+            if !current.is_empty() {
+                count += self.count_word_pieces(&current);
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn should_count_whitespace_tokens() {
+        let counter = WhitespaceTokenCounter;
+        assert_eq!(counter.count_tokens(""), 0);
+        assert_eq!(counter.count_tokens("  hello   world  "), 2);
+    }
+
+    #[test]
+    fn should_estimate_tokens_from_characters() {
+        let counter = CharsPerTokenCounter::new(4.0);
+        assert_eq!(counter.count_tokens(""), 0);
+        assert_eq!(counter.count_tokens("abcd"), 1);
+        assert_eq!(counter.count_tokens("abcde"), 2);
+        // Whitespace does not count towards the character budget
+        assert_eq!(counter.count_tokens("ab cd"), 1);
+    }
+
+    #[test]
+    fn should_pick_heuristic_per_model() {
+        assert_eq!(
+            CharsPerTokenCounter::for_model("cohere.embed-multilingual-v3").chars_per_token(),
+            3.0
+        );
+        assert_eq!(
+            CharsPerTokenCounter::for_model("cohere.embed-english-v3").chars_per_token(),
+            3.5
+        );
+        assert_eq!(
+            CharsPerTokenCounter::for_model("amazon.titan-embed-text-v2:0").chars_per_token(),
+            4.5
+        );
+        assert_eq!(
+            CharsPerTokenCounter::for_model("unknown").chars_per_token(),
+            4.0
+        );
+    }
+
+    #[test]
+    fn should_split_words_into_word_pieces() {
+        let counter = WordPieceTokenCounter::from_vocab(
+            ["un", "##aff", "##able", "run", "##ning", "the"],
+            true,
+        );
+
+        assert_eq!(counter.count_tokens("unaffable"), 3);
+        assert_eq!(counter.count_tokens("The running"), 3);
+        // Punctuation is its own token, unknown words count as a single [UNK]
+        assert_eq!(counter.count_tokens("running, xyz!"), 5);
+    }
+
+    #[test]
+    fn should_load_vocabulary_from_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "[UNK]\nhello\n##s\n").unwrap();
+
+        let counter = WordPieceTokenCounter::from_vocab_file(file.path(), true).unwrap();
+
+        assert_eq!(counter.vocab_size(), 3);
+        assert_eq!(counter.count_tokens("Hellos"), 2);
+    }
+
+    #[test]
+    fn should_fail_for_missing_vocabulary_file() {
+        let result = WordPieceTokenCounter::from_vocab_file(Path::new("/no/such/vocab.txt"), true);
+        assert!(result.is_err());
+    }
+}
@@ -70,6 +70,27 @@ impl BedrockClient {
         Err(last_error.unwrap())
     }
 
+    /// Calls Claude and collects the streamed response into a single string
+    pub async fn complete(&self, messages: Vec<ChatMessage>) -> Result<String> {
+        use futures::StreamExt;
+
+        let mut stream = self.call_claude(messages).await?;
+        let mut response = String::new();
+
+        while let Some(event) = stream.next().await {
+            match event? {
+                StreamEvent::ContentBlockDelta { text } => response.push_str(&text),
+                StreamEvent::Error { message } => {
+                    anyhow::bail!("Bedrock stream error: {}", message)
+                }
+                StreamEvent::MessageStop => break,
+                _ => {}
+            }
+        }
+
+        Ok(response)
+    }
+
     async fn try_call_claude(
         &self,
         messages: Vec<ChatMessage>,
@@ -1,99 +1,34 @@
-use crate::config::Config;
-use crate::sse::{create_assistant_output_event, create_tool_usage_event};
+use crate::condense::QueryCondenser;
+use crate::config::{Config, RetrievalStrategy};
+use crate::expansion::QueryExpander;
+use crate::rerank::{create_reranker, Reranker};
+use crate::sse::{
+    create_assistant_output_event, create_citations_event, create_query_rewrite_event,
+    create_rerank_event, create_sources_event, create_tool_usage_event,
+};
 use anyhow::{Context, Result};
 use axum::response::sse::Event;
-use chrono::Utc;
-use embeddings::{create_embedding_provider, ChunkConfig, EmbeddingProvider, TextChunker};
+use embeddings::{
+    create_embedding_provider, create_token_counter, ChunkConfig, EmbeddingProvider, TextChunk,
+    TextChunker,
+};
 use futures::stream::Stream;
 use llm::{BedrockClient, ChatMessage, ModelConfig, StreamEvent};
-use log::info;
-use std::collections::HashMap;
+use log::{info, warn};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
-use store::{Document, DocumentChunk, SearchResult, VectorStore};
+use std::sync::Arc;
+use store::hybrid::reciprocal_rank_fusion;
+use store::{
+    Collection, CollectionStats, DocumentChunk, DocumentMetadata, HybridSearch, MetadataFilter,
+    ParentDocument, PgVectorStore, SearchOptions, SearchResult, SourceSpan, SqliteVectorStore,
+    VectorStore, DEFAULT_COLLECTION,
+};
 use store::{Message, RedisSessionStore, Role};
 use tooling::{FileSummarizerTool, ToolInput, ToolRegistry};
 use uuid::Uuid;
 
-// Simple in-memory vector store for testing
-pub struct InMemoryVectorStore {
-    documents: Arc<Mutex<HashMap<Uuid, Document>>>,
-}
-
-impl Default for InMemoryVectorStore {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl InMemoryVectorStore {
-    pub fn new() -> Self {
-        Self {
-            documents: Arc::new(Mutex::new(HashMap::new())),
-        }
-    }
-
-    pub async fn insert_document(&self, chunk: DocumentChunk) -> Result<()> {
-        let document = Document {
-            id: Uuid::new_v4(),
-            file_name: chunk.file_name,
-            chunk_id: chunk.chunk_id,
-            content: chunk.content,
-            embedding: chunk.embedding,
-            created_at: Utc::now(),
-        };
-
-        self.documents.lock().unwrap().insert(document.id, document);
-        Ok(())
-    }
-
-    pub async fn search_similar(
-        &self,
-        _query_embedding: Vec<f32>,
-        limit: usize,
-    ) -> Result<Vec<SearchResult>> {
-        // For testing, return all stored documents as relevant matches
-        // In a real vector store, this would do similarity search
-        let documents = self.documents.lock().unwrap();
-        let results: Vec<SearchResult> = documents
-            .values()
-            .take(limit)
-            .map(|doc| SearchResult::new(doc.clone(), 0.9)) // Mock high similarity score
-            .collect();
-        Ok(results)
-    }
-}
-
-pub enum AnyVectorStore {
-    Real(VectorStore),
-    InMemory(InMemoryVectorStore),
-}
-
-impl AnyVectorStore {
-    pub async fn insert_document(&self, chunk: DocumentChunk) -> Result<()> {
-        match self {
-            AnyVectorStore::Real(store) => {
-                store.insert_document(chunk).await?;
-                Ok(())
-            }
-            AnyVectorStore::InMemory(store) => store.insert_document(chunk).await,
-        }
-    }
-
-    pub async fn search_similar(
-        &self,
-        query_embedding: Vec<f32>,
-        limit: usize,
-    ) -> Result<Vec<SearchResult>> {
-        match self {
-            AnyVectorStore::Real(store) => {
-                store.search_similar(query_embedding, limit as i32).await
-            }
-            AnyVectorStore::InMemory(store) => store.search_similar(query_embedding, limit).await,
-        }
-    }
-}
-
 /// Tool call detection logic extracted for better separation of concerns
 pub struct ToolCallDetector {
     document_dir: String,
@@ -140,8 +75,16 @@ pub struct AgentService {
     config: Config,
     session_store: Arc<RedisSessionStore>,
     embeddings_client: EmbeddingClient,
-    vector_store: Arc<AnyVectorStore>,
+    /// Clients of collections configured with their own embedding model
+    collection_embedders: HashMap<String, EmbeddingClient>,
+    vector_store: Arc<dyn VectorStore>,
+    /// Every served collection by name, including the default one
+    collections: BTreeMap<String, Arc<dyn VectorStore>>,
     llm_client: Arc<BedrockClient>,
+    reranker: Arc<dyn Reranker>,
+    /// Rewrites follow-up questions before retrieval when enabled
+    condenser: Option<QueryCondenser>,
+    expander: QueryExpander,
     tool_registry: Arc<ToolRegistry>,
     text_chunker: TextChunker,
 }
@@ -152,8 +95,16 @@ impl std::fmt::Debug for AgentService {
             .field("config", &self.config)
             .field("session_store", &"RedisSessionStore<...>")
             .field("embeddings_client", &"EmbeddingClient<...>")
-            .field("vector_store", &"AnyVectorStore<...>")
+            .field(
+                "collection_embedders",
+                &self.collection_embedders.keys().collect::<Vec<_>>(),
+            )
+            .field("vector_store", &self.vector_store.collection_name())
+            .field("collections", &self.collections.keys().collect::<Vec<_>>())
             .field("llm_client", &"BedrockClient<...>")
+            .field("reranker", &self.config.rerank.provider)
+            .field("condenser", &self.condenser.is_some())
+            .field("strategy", &self.config.retrieval.strategy)
             .field("tool_registry", &"ToolRegistry<...>")
             .field("text_chunker", &"TextChunker<...>")
             .finish()
@@ -162,6 +113,17 @@ impl std::fmt::Debug for AgentService {
 
 type EmbeddingClient = Box<dyn EmbeddingProvider>;
 
+/// Per-request retrieval options
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// Metadata filter expression, see [`MetadataFilter`]
+    pub filter: Option<String>,
+    /// Collections to search; all served collections when `None`
+    pub collections: Option<Vec<String>>,
+    /// Strategy for every searched collection, overriding the configured ones
+    pub strategy: Option<RetrievalStrategy>,
+}
+
 pub struct AgentResponse {
     pub session_id: Uuid,
     pub events: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send + 'static>>,
@@ -182,31 +144,7 @@ impl AgentService {
             .await
             .context("Failed to create embedding provider")?;
 
-        // Initialize vector store (only use in-memory for testing)
-        let pg_cfg = config.pgvector.with_env_overrides();
-        let vector_store = if pg_cfg.url.starts_with("sqlite://") {
-            // For testing, create an in-memory vector store alternative
-            Arc::new(AnyVectorStore::InMemory(InMemoryVectorStore::new()))
-        } else if pg_cfg.url.starts_with("postgresql://") {
-            // Create and initialize PostgreSQL vector store with correct embedding dimensions
-            let embedding_dimensions = embeddings_client.dimension();
-            info!(
-                "Initializing PostgreSQL vector store with {} dimensions",
-                embedding_dimensions
-            );
-
-            let store = VectorStore::new_with_dimensions(&pg_cfg.url, embedding_dimensions)
-                .await
-                .context("Failed to connect to PostgreSQL vector store")?;
-
-            Arc::new(AnyVectorStore::Real(store))
-        } else {
-            // Invalid URL - not sqlite or postgresql
-            anyhow::bail!(
-                "Invalid database URL: {}, must start with 'sqlite://' or 'postgresql://'",
-                pg_cfg.url
-            );
-        };
+        let vector_store = create_vector_store(&config, embeddings_client.dimension()).await?;
 
         // Initialize LLM client with configured models
         let llm_cfg = config.llm.with_env_overrides();
@@ -229,14 +167,17 @@ impl AgentService {
             .context("Failed to register file summarizer tool")?;
         let tool_registry = Arc::new(tool_registry);
 
-        // Initialize text chunker
+        // Initialize text chunker, sized in the embedding model's tokens
         let chunk_config = ChunkConfig {
             chunk_size: 500,
             overlap_size: 100,
+            token_counter: create_token_counter(&config.embedding)
+                .context("Failed to create token counter")?,
+            strategy: config.ingestion.chunk_strategy.into(),
         };
         let text_chunker = TextChunker::new(chunk_config);
 
-        Ok(Self {
+        Self::with_clients(
             config,
             session_store,
             embeddings_client,
@@ -244,7 +185,8 @@ impl AgentService {
             llm_client,
             tool_registry,
             text_chunker,
-        })
+        )
+        .await
     }
 
     // Dependency-injection friendly constructor for testing and composition
@@ -252,27 +194,200 @@ impl AgentService {
         config: Config,
         session_store: Arc<RedisSessionStore>,
         embeddings_client: EmbeddingClient,
-        vector_store: Arc<AnyVectorStore>,
+        vector_store: Arc<dyn VectorStore>,
         llm_client: Arc<BedrockClient>,
         tool_registry: Arc<ToolRegistry>,
         text_chunker: TextChunker,
     ) -> Result<Self> {
+        let mut collections = BTreeMap::from([(
+            vector_store.collection_name().to_string(),
+            Arc::clone(&vector_store),
+        )]);
+        let mut collection_embedders = HashMap::new();
+        for collection_cfg in &config.collections {
+            // A collection with its own embedding model gets vectors of its dimension
+            let dimension = match &collection_cfg.embedding {
+                Some(embedding_cfg) => {
+                    let embedder = create_embedding_provider(embedding_cfg)
+                        .await
+                        .with_context(|| {
+                            format!(
+                                "Failed to create embedding provider for collection '{}'",
+                                collection_cfg.name
+                            )
+                        })?;
+                    let dimension = embedder.dimension();
+                    collection_embedders.insert(collection_cfg.name.clone(), embedder);
+                    dimension
+                }
+                None => embeddings_client.dimension(),
+            };
+            let collection = Collection::new(
+                &collection_cfg.name,
+                collection_model_name(&config, &collection_cfg.name),
+                dimension,
+            );
+            let store = vector_store
+                .collection_store(&collection)
+                .await
+                .with_context(|| format!("Failed to open collection '{}'", collection.name))?;
+            collections.insert(collection.name, store);
+        }
+
+        let reranker = create_reranker(&config.rerank, Arc::clone(&llm_client))
+            .await
+            .context("Failed to create reranker")?;
+        let expander = QueryExpander::new(Arc::clone(&llm_client), config.retrieval.multi_queries);
+        let condense_cfg = &config.condense;
+        let condenser = if condense_cfg.enabled {
+            let llm_client = llm_client_for_model(
+                condense_cfg.model.as_deref(),
+                condense_cfg.aws_region.as_deref(),
+                Arc::clone(&llm_client),
+            )
+            .await
+            .context("Failed to create query condensation client")?;
+            Some(QueryCondenser::new(
+                llm_client,
+                condense_cfg.history_turns,
+                condense_cfg.cache_size,
+            ))
+        } else {
+            None
+        };
+
         Ok(Self {
             config,
             session_store,
             embeddings_client,
+            collection_embedders,
             vector_store,
+            collections,
             llm_client,
+            reranker,
+            condenser,
+            expander,
             tool_registry,
             text_chunker,
         })
     }
 
+    /// Embedding client of `collection`: its own when configured, otherwise the server's
+    fn embedder_for(&self, collection: &str) -> &dyn EmbeddingProvider {
+        self.collection_embedders
+            .get(collection)
+            .unwrap_or(&self.embeddings_client)
+            .as_ref()
+    }
+
+    /// Groups `stores` by the retrieval strategy used for them: the requested one,
+    /// else the collection's, else the `[retrieval]` default
+    fn stores_by_strategy(
+        &self,
+        stores: &[Arc<dyn VectorStore>],
+        requested: Option<RetrievalStrategy>,
+    ) -> BTreeMap<RetrievalStrategy, Vec<Arc<dyn VectorStore>>> {
+        let mut groups: BTreeMap<RetrievalStrategy, Vec<Arc<dyn VectorStore>>> = BTreeMap::new();
+        for store in stores {
+            let strategy = requested
+                .or_else(|| {
+                    self.config
+                        .collections
+                        .iter()
+                        .find(|collection| collection.name == store.collection_name())
+                        .and_then(|collection| collection.strategy)
+                })
+                .unwrap_or(self.config.retrieval.strategy);
+            groups.entry(strategy).or_default().push(Arc::clone(store));
+        }
+        groups
+    }
+
+    /// Standalone rewrite of `question` given the session history, or `None`
+    /// when condensation is disabled, not needed or fails
+    async fn condense_query(&self, session_id: &Uuid, question: &str) -> Option<String> {
+        let condenser = self.condenser.as_ref()?;
+        let conversation = match self.session_store.get(session_id).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                warn!("Failed to load history for query condensation: {}", e);
+                return None;
+            }
+        };
+        match condenser.condense(&conversation).await {
+            Ok(Some(query)) if query != question => {
+                info!("Condensed query: {}", query);
+                Some(query)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Query condensation failed, using the question as is: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Reranks search candidates, keeping the search order when the reranker fails
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        if !self.config.rerank.enabled() {
+            return results;
+        }
+        match self.reranker.rerank(query, results.clone()).await {
+            Ok(reranked) => reranked,
+            Err(e) => {
+                warn!("Reranking failed, keeping search order: {}", e);
+                results
+            }
+        }
+    }
+
+    /// Stores of the requested collections, or of every served collection
+    fn collection_stores(&self, names: Option<&[String]>) -> Result<Vec<Arc<dyn VectorStore>>> {
+        match names {
+            None => Ok(self.collections.values().cloned().collect()),
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    self.collections
+                        .get(name)
+                        .cloned()
+                        .ok_or_else(|| anyhow::anyhow!("Unknown collection: {}", name))
+                })
+                .collect(),
+        }
+    }
+
+    /// Chunk, file and parent counts of every served collection
+    pub async fn collection_stats(&self) -> Result<Vec<CollectionStats>> {
+        let mut stats = Vec::new();
+        for store in self.collections.values() {
+            stats.push(store.collection_stats().await?);
+        }
+        Ok(stats)
+    }
+
     pub async fn process_message(
         &self,
         session_id: Uuid,
         messages: Vec<Message>,
     ) -> Result<Vec<Event>> {
+        self.process_message_with_options(session_id, messages, QueryOptions::default())
+            .await
+    }
+
+    pub async fn process_message_with_options(
+        &self,
+        session_id: Uuid,
+        messages: Vec<Message>,
+        options: QueryOptions,
+    ) -> Result<Vec<Event>> {
+        let filter = options
+            .filter
+            .as_deref()
+            .map(MetadataFilter::parse)
+            .transpose()?;
+        let stores = self.collection_stores(options.collections.as_deref())?;
+
         for message in &messages {
             self.session_store
                 .append(&session_id, message.clone())
@@ -324,39 +439,109 @@ impl AgentService {
             }
         }
 
-        // Create embeddings for the user query
-        let query_embedding = match self
-            .embeddings_client
-            .embed(vec![user_message.content.clone()])
+        // Resolve follow-up questions against the conversation before retrieval
+        let query = match self
+            .condense_query(&session_id, &user_message.content)
             .await
         {
-            Ok(embeddings) => embeddings.into_iter().next(),
-            Err(e) => {
-                events.push(create_assistant_output_event(&format!(
-                    "I'm having trouble processing your request due to an embedding error: {}",
-                    e
-                )));
-                return Ok(events);
+            Some(rewritten) => {
+                events.push(create_query_rewrite_event(
+                    &user_message.content,
+                    &rewritten,
+                ));
+                rewritten
             }
+            None => user_message.content.clone(),
         };
 
-        let query_embedding = match query_embedding {
-            Some(embedding) => embedding,
-            None => {
-                events.push(create_assistant_output_event(
-                    "I'm having trouble generating embeddings for your query.",
-                ));
-                return Ok(events);
+        // Embed the texts each collection's retrieval strategy searches for
+        let mut searches: Vec<QuerySearch> = Vec::new();
+        for (strategy, strategy_stores) in self.stores_by_strategy(&stores, options.strategy) {
+            let texts = match self.expander.expand(&query, strategy).await {
+                Ok(texts) => texts,
+                Err(e) => {
+                    warn!(
+                        "Query expansion for {:?} failed, searching the question only: {}",
+                        strategy, e
+                    );
+                    vec![query.clone()]
+                }
+            };
+            // Collections sharing an embedding client share the query embeddings
+            let mut embeddings_by_client: HashMap<Option<&str>, Vec<Vec<f32>>> = HashMap::new();
+            for store in &strategy_stores {
+                let client_key = self
+                    .collection_embedders
+                    .get_key_value(store.collection_name())
+                    .map(|(name, _)| name.as_str());
+                if let Entry::Vacant(entry) = embeddings_by_client.entry(client_key) {
+                    let embedder = self.embedder_for(store.collection_name());
+                    let query_embeddings = match embedder.embed(texts.clone()).await {
+                        Ok(embeddings) if !embeddings.is_empty() => embeddings,
+                        Ok(_) => {
+                            events.push(create_assistant_output_event(
+                                "I'm having trouble generating embeddings for your query.",
+                            ));
+                            return Ok(events);
+                        }
+                        Err(e) => {
+                            events.push(create_assistant_output_event(&format!(
+                                "I'm having trouble processing your request due to an embedding error: {}",
+                                e
+                            )));
+                            return Ok(events);
+                        }
+                    };
+                    entry.insert(query_embeddings);
+                }
+                for (text, embedding) in texts.iter().zip(&embeddings_by_client[&client_key]) {
+                    searches.push(QuerySearch {
+                        store: Arc::clone(store),
+                        text: text.clone(),
+                        embedding: embedding.clone(),
+                    });
+                }
             }
-        };
+        }
 
         // Search for relevant documents
-        let search_results = match self.vector_store.search_similar(query_embedding, 5).await {
-            Ok(results) => results,
+        let retrieval = &self.config.retrieval;
+        let search_options = SearchOptions {
+            expand_parents: retrieval.expand_parents,
+            filter,
+            hybrid: retrieval.hybrid.then(|| HybridSearch {
+                query_text: query.clone(),
+                vector_weight: retrieval.vector_weight,
+                lexical_weight: retrieval.lexical_weight,
+                rrf_k: retrieval.rrf_k,
+            }),
+        };
+        let mut candidates = crate::retrieval::candidate_count(retrieval);
+        if self.config.rerank.enabled() {
+            candidates = candidates.max(self.config.rerank.candidates);
+        }
+        let search_results = match search_collections(
+            &searches,
+            candidates,
+            &search_options,
+            retrieval.rrf_k,
+        )
+        .await
+        {
+            Ok(results) => {
+                let results = self.rerank(&query, results).await;
+                let selected = crate::retrieval::select(results, retrieval);
+                // Expose the scores the reranker ordered the kept chunks by
+                if selected.iter().any(|result| result.rerank_score.is_some()) {
+                    events.push(create_rerank_event(&selected));
+                }
+                selected
+            }
             Err(e) => {
                 let error_msg = if e.to_string().contains("embedding dimensions") {
                     format!(
-                        "Vector search failed due to embedding dimension mismatch. This usually means the database was created with different embedding dimensions than the current provider (Bedrock Cohere uses 1024). Please recreate the database or run initialization again. Details: {}",
+                        "Vector search failed due to embedding dimension mismatch. This usually means the collection was embedded with a different model than the current provider ({} dimensions). Configure a new collection so the documents are re-embedded. Details: {}",
+                        self.embeddings_client.dimension(),
                         e
                     )
                 } else {
@@ -368,6 +553,10 @@ impl AgentService {
             }
         };
 
+        // Tell the client which numbered sources the answer may cite
+        let sources = crate::citations::sources(&search_results);
+        events.push(create_sources_event(&sources));
+
         // Convert messages to LLM format
         let session_messages = self
             .session_store
@@ -392,6 +581,7 @@ impl AgentService {
                 use futures::StreamExt;
 
                 let mut full_response = String::new();
+                let mut completed = false;
 
                 while let Some(stream_event) = stream.next().await {
                     match stream_event {
@@ -402,8 +592,7 @@ impl AgentService {
                             }
                         }
                         Ok(llm::StreamEvent::MessageStop) => {
-                            // Add stream end event when LLM completes
-                            events.push(crate::sse::create_stream_end_event());
+                            completed = true;
                             break;
                         }
                         Ok(_) => {
@@ -420,6 +609,22 @@ impl AgentService {
                     }
                 }
 
+                if !sources.is_empty() && !full_response.is_empty() {
+                    let check = crate::citations::check_citations(&full_response, sources.len());
+                    if !check.invalid.is_empty() {
+                        warn!(
+                            "Answer cites {:?} but only {} sources were provided",
+                            check.invalid,
+                            sources.len()
+                        );
+                    }
+                    events.push(create_citations_event(&check));
+                }
+                if completed {
+                    // Add stream end event when LLM completes
+                    events.push(crate::sse::create_stream_end_event());
+                }
+
                 // Store the complete response in session
                 if !full_response.is_empty() {
                     let assistant_message = Message {
@@ -450,14 +655,63 @@ impl AgentService {
     }
 
     pub async fn add_document(&self, file_name: &str, content: &str) -> Result<()> {
-        // Chunk the document content
-        let chunks = self.text_chunker.chunk_text(content);
+        self.add_document_with_metadata(file_name, content, DocumentMetadata::default())
+            .await
+    }
+
+    pub async fn add_document_with_metadata(
+        &self,
+        file_name: &str,
+        content: &str,
+        metadata: DocumentMetadata,
+    ) -> Result<()> {
+        self.add_document_to_collection(self.vector_store.as_ref(), file_name, content, metadata)
+            .await
+    }
+
+    async fn add_document_to_collection(
+        &self,
+        store: &dyn VectorStore,
+        file_name: &str,
+        content: &str,
+        metadata: DocumentMetadata,
+    ) -> Result<()> {
+        // Front matter is metadata, not content; spans still point into the full file
+        let body_start =
+            crate::metadata::front_matter_len(std::path::Path::new(file_name), content);
+        let offset = SpanOffset::of(&content[..body_start]);
+        let content = &content[body_start..];
+
+        // Chunk the document content (source files are split on code items)
+        let chunks = self.text_chunker.chunk_file(file_name, content);
+
+        let ingestion = &self.config.ingestion;
+        let summary = if ingestion.contextual_headers && ingestion.document_summary {
+            self.summarize_document(file_name, content).await
+        } else {
+            None
+        };
 
+        // Sections split into several chunks are stored as their parent
+        let parents = parent_sections(
+            file_name,
+            content,
+            &chunks,
+            self.config.ingestion.parent_max_chars,
+            &offset,
+        );
+
+        let mut document_chunks = Vec::with_capacity(chunks.len());
         for (chunk_id, chunk) in chunks.into_iter().enumerate() {
-            // Generate embedding for chunk
+            // Generate embedding for chunk; the stored content stays the original text
+            let embedding_text = if ingestion.contextual_headers {
+                contextual_embedding_text(file_name, &chunk, summary.as_deref())
+            } else {
+                chunk.embedding_text()
+            };
             let embeddings = self
-                .embeddings_client
-                .embed(vec![chunk.content.clone()])
+                .embedder_for(store.collection_name())
+                .embed(vec![embedding_text])
                 .await
                 .context("Failed to generate embeddings")?;
 
@@ -466,31 +720,114 @@ impl AgentService {
                 .next()
                 .ok_or_else(|| anyhow::anyhow!("No embedding generated"))?;
 
-            // Create document chunk
-            let document_chunk = DocumentChunk {
-                file_name: file_name.to_string(),
-                chunk_id,
-                content: chunk.content,
-                embedding,
+            // Create document chunk, keeping where it came from in the source file
+            let span = source_span(&chunk, &chunk, &offset);
+            let chunk_metadata = DocumentMetadata {
+                heading_path: chunk.heading_path,
+                ..metadata.clone()
             };
-
-            // Insert into vector store
-            self.vector_store.insert_document(document_chunk).await?;
+            let mut document_chunk =
+                DocumentChunk::new(file_name.to_string(), chunk_id, chunk.content, embedding)
+                    .with_span(span)
+                    .with_metadata(chunk_metadata);
+            if let Some(parent) = parents.get(&chunk.section_id) {
+                document_chunk = document_chunk.with_parent(parent.id);
+            }
+            document_chunks.push(document_chunk);
         }
 
+        // Replace any earlier version of the file in one step
+        let source = metadata.source_path.as_deref().unwrap_or(file_name);
+        let parents: Vec<ParentDocument> = parents.into_values().collect();
+        let started = std::time::Instant::now();
+        let stored = store
+            .upsert_document(source, &parents, document_chunks)
+            .await?;
+        info!(
+            "Stored {} chunks of {} in {:?}",
+            stored.len(),
+            file_name,
+            started.elapsed()
+        );
+
         Ok(())
     }
 
+    /// One-sentence LLM summary of a document, or `None` when the LLM is unavailable
+    async fn summarize_document(&self, file_name: &str, content: &str) -> Option<String> {
+        let excerpt: String = content
+            .chars()
+            .take(self.config.ingestion.summary_max_chars)
+            .collect();
+        let prompt = format!(
+            "Summarize what the document \"{}\" is about in one sentence. \
+             Reply with the sentence only.\n\n<document>\n{}\n</document>",
+            file_name, excerpt
+        );
+
+        match self
+            .llm_client
+            .complete(vec![ChatMessage::user(prompt)])
+            .await
+        {
+            Ok(summary) if !summary.trim().is_empty() => {
+                Some(summary.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!(
+                    "Failed to summarize {} for contextual headers: {}",
+                    file_name, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Loads the default document directory into the default collection and each
+    /// configured collection's directory into that collection
     pub async fn load_documents(&self) -> Result<()> {
+        self.load_directory(self.vector_store.as_ref(), &self.config.data.document_dir)
+            .await?;
+
+        for collection_cfg in &self.config.collections {
+            let store = &self.collections[&collection_cfg.name];
+            info!(
+                "Loading collection '{}' from {}",
+                collection_cfg.name, collection_cfg.document_dir
+            );
+            self.load_directory(store.as_ref(), &collection_cfg.document_dir)
+                .await
+                .with_context(|| format!("Failed to load collection '{}'", collection_cfg.name))?;
+        }
+
+        Ok(())
+    }
+
+    /// Settings that decide how files are chunked and embedded; files stored
+    /// under other settings are re-ingested
+    fn ingestion_settings(&self, collection: &str) -> String {
+        let chunking = self.text_chunker.config();
+        format!(
+            "model={} chunk_size={} overlap_size={} strategy={:?} ingestion={}",
+            collection_model_name(&self.config, collection),
+            chunking.chunk_size,
+            chunking.overlap_size,
+            chunking.strategy,
+            serde_json::to_string(&self.config.ingestion).unwrap_or_default()
+        )
+    }
+
+    async fn load_directory(&self, store: &dyn VectorStore, document_dir: &str) -> Result<()> {
         use std::path::Path;
         use tokio::fs;
 
-        let documents_dir = Path::new(&self.config.data.document_dir);
+        let documents_dir = Path::new(document_dir);
 
         if !documents_dir.exists() {
             return Err(anyhow::anyhow!(
                 "Documents directory does not exist: {}",
-                self.config.data.document_dir
+                document_dir
             ));
         }
 
@@ -505,8 +842,13 @@ impl AgentService {
         {
             let path = entry.path();
 
-            // Only process .txt files
-            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("txt") {
+            // Only process plain text, Markdown and supported source files
+            if path.is_file()
+                && matches!(
+                    path.extension().and_then(|s| s.to_str()),
+                    Some("txt" | "md" | "rs" | "py")
+                )
+            {
                 let file_name = path
                     .file_name()
                     .and_then(|s| s.to_str())
@@ -517,9 +859,25 @@ impl AgentService {
                     .await
                     .context(format!("Failed to read file: {:?}", path))?;
 
+                let metadata = crate::metadata::document_metadata(
+                    &path,
+                    &content,
+                    &self.ingestion_settings(store.collection_name()),
+                )
+                .await;
+
+                // Unchanged files keep their chunks and need no re-embedding
+                if let Some(source) = &metadata.source_path {
+                    let stored = store.get_document(source).await?;
+                    if stored.is_some_and(|stored| stored.content_hash == metadata.checksum) {
+                        info!("Skipping unchanged document: {}", file_name);
+                        continue;
+                    }
+                }
+
                 // Add document to vector store
                 info!("Loading document: {}", file_name);
-                self.add_document(file_name, &content)
+                self.add_document_to_collection(store, file_name, &content, metadata)
                     .await
                     .context(format!("Failed to add document: {}", file_name))?;
 
@@ -547,13 +905,20 @@ impl AgentService {
         // Add context from search results if any
         if !search_results.is_empty() {
             let mut context = String::from("Context information from relevant documents:\n\n");
-            for result in search_results {
+            for (index, result) in search_results.iter().enumerate() {
+                // Uses the enclosing section when the search expanded parents
                 context.push_str(&format!(
-                    "From {}: {}\n\n",
-                    result.document.file_name, result.document.content
+                    "[{}] From {}: {}\n\n",
+                    index + 1,
+                    result.document.file_name,
+                    result.context()
                 ));
             }
-            context.push_str("Based on the above context, please answer the user's question.");
+            context.push_str(
+                "Based on the above context, please answer the user's question. \
+                 Cite the context blocks you use by their number in square brackets, \
+                 for example [1] or [1, 2].",
+            );
 
             llm_messages.push(ChatMessage {
                 role: "user".to_string(),
@@ -581,9 +946,207 @@ impl AgentService {
     }
 }
 
+/// Position of the chunked text within the original file
+#[derive(Debug, Default)]
+struct SpanOffset {
+    bytes: usize,
+    chars: usize,
+    lines: usize,
+}
+
+impl SpanOffset {
+    /// Offset past `prefix`, the part of the file that was not chunked
+    fn of(prefix: &str) -> Self {
+        Self {
+            bytes: prefix.len(),
+            chars: prefix.chars().count(),
+            lines: prefix.matches('\n').count(),
+        }
+    }
+}
+
+/// Span in the original file from the start of `first` to the end of `last`
+fn source_span(first: &TextChunk, last: &TextChunk, offset: &SpanOffset) -> SourceSpan {
+    SourceSpan {
+        start_byte: offset.bytes + first.start_pos,
+        end_byte: offset.bytes + last.end_pos,
+        start_char: offset.chars + first.start_char,
+        end_char: offset.chars + last.end_char,
+        start_line: offset.lines + first.start_line,
+        end_line: offset.lines + last.end_line,
+    }
+}
+
+/// Parent sections keyed by section id, for sections cut into more than one chunk
+/// and no longer than `max_chars`
+fn parent_sections(
+    file_name: &str,
+    content: &str,
+    chunks: &[TextChunk],
+    max_chars: usize,
+    offset: &SpanOffset,
+) -> HashMap<usize, ParentDocument> {
+    chunks
+        .chunk_by(|a, b| a.section_id == b.section_id)
+        .filter(|section| section.len() > 1)
+        .filter_map(|section| {
+            let (first, last) = (&section[0], &section[section.len() - 1]);
+            let span = source_span(first, last, offset);
+            (span.end_char - span.start_char <= max_chars).then(|| {
+                let parent = ParentDocument::new(
+                    file_name.to_string(),
+                    content[first.start_pos..last.end_pos].to_string(),
+                    first.heading_path.clone(),
+                )
+                .with_span(span);
+                (first.section_id, parent)
+            })
+        })
+        .collect()
+}
+
+/// Text embedded for a chunk when contextual headers are enabled: the file name,
+/// section heading path and optional document summary, followed by the chunk
+fn contextual_embedding_text(file_name: &str, chunk: &TextChunk, summary: Option<&str>) -> String {
+    let mut header = vec![format!("Document: {}", file_name)];
+    // Code chunks carry their scopes in their own context header
+    if !chunk.heading_path.is_empty() && chunk.context_header.is_none() {
+        header.push(format!("Section: {}", chunk.heading_path.join(" > ")));
+    }
+    if let Some(summary) = summary {
+        header.push(format!("Summary: {}", summary));
+    }
+    if let Some(context) = &chunk.context_header {
+        header.push(context.clone());
+    }
+    format!("{}\n\n{}", header.join("\n"), chunk.content)
+}
+
+/// Embedding model recorded on collections created by this server
+fn embedding_model_name(config: &Config) -> String {
+    config
+        .embedding
+        .model
+        .clone()
+        .unwrap_or_else(|| config.embedding.provider.clone())
+}
+
+/// Embedding model of `collection`: its own when configured, otherwise the server's
+fn collection_model_name(config: &Config, collection: &str) -> String {
+    config
+        .collections
+        .iter()
+        .find(|collection_cfg| collection_cfg.name == collection)
+        .and_then(|collection_cfg| collection_cfg.embedding.as_ref())
+        .map(|embedding| {
+            embedding
+                .model
+                .clone()
+                .unwrap_or_else(|| embedding.provider.clone())
+        })
+        .unwrap_or_else(|| embedding_model_name(config))
+}
+
+/// Client for `model` when set, otherwise `default`
+pub(crate) async fn llm_client_for_model(
+    model: Option<&str>,
+    aws_region: Option<&str>,
+    default: Arc<BedrockClient>,
+) -> Result<Arc<BedrockClient>> {
+    let Some(model) = model else {
+        return Ok(default);
+    };
+    let model_config = ModelConfig {
+        primary_model: model.to_string(),
+        fallback_model: model.to_string(),
+        ..ModelConfig::default()
+    };
+    let region = aws_region.unwrap_or("eu-central-1");
+    Ok(Arc::new(
+        BedrockClient::new_with_region(model_config, region).await?,
+    ))
+}
+
+/// Opens the vector store the configured database URL points at
+async fn create_vector_store(config: &Config, dimensions: usize) -> Result<Arc<dyn VectorStore>> {
+    let pg_cfg = config.pgvector.with_env_overrides();
+    let collection = Collection::new(
+        pg_cfg.collection.as_deref().unwrap_or(DEFAULT_COLLECTION),
+        embedding_model_name(config),
+        dimensions,
+    );
+
+    if pg_cfg.url.starts_with("sqlite://") {
+        info!(
+            "Opening SQLite vector store collection '{}' with {} dimensions",
+            collection.name, dimensions
+        );
+        let store = SqliteVectorStore::new_with_collection(&pg_cfg.url, collection)
+            .await
+            .context("Failed to open SQLite vector store")?;
+        Ok(Arc::new(store))
+    } else if pg_cfg.url.starts_with("postgresql://") {
+        // Create and initialize PostgreSQL vector store with correct embedding dimensions
+        info!(
+            "Initializing PostgreSQL vector store collection '{}' with {} dimensions",
+            collection.name, dimensions
+        );
+        let store = PgVectorStore::new_with_collection(&pg_cfg.url, collection, pg_cfg.index)
+            .await
+            .context("Failed to connect to PostgreSQL vector store")?;
+        Ok(Arc::new(store))
+    } else {
+        anyhow::bail!(
+            "Invalid database URL: {}, must start with 'sqlite://' or 'postgresql://'",
+            pg_cfg.url
+        );
+    }
+}
+
+/// One store searched for one query text
+struct QuerySearch {
+    store: Arc<dyn VectorStore>,
+    /// Text that was embedded, also used for the hybrid full-text leg
+    text: String,
+    embedding: Vec<f32>,
+}
+
+/// Runs each store and query embedding search, merging the rankings with
+/// reciprocal rank fusion when there are several
+async fn search_collections(
+    searches: &[QuerySearch],
+    limit: usize,
+    options: &SearchOptions,
+    rrf_k: f32,
+) -> Result<Vec<SearchResult>> {
+    let mut ranked_lists = Vec::new();
+    for search in searches {
+        // Each expanded text gets its own full-text leg, so a lexical hit is
+        // only counted again when another text also matches it
+        let mut options = options.clone();
+        if let Some(hybrid) = &mut options.hybrid {
+            hybrid.query_text = search.text.clone();
+        }
+        let results = search
+            .store
+            .search_similar(search.embedding.clone(), limit, &options)
+            .await?;
+        ranked_lists.push((1.0, results));
+    }
+
+    if ranked_lists.len() == 1 {
+        return Ok(ranked_lists.remove(0).1);
+    }
+
+    let mut results = reciprocal_rank_fusion(ranked_lists, rrf_k);
+    results.truncate(limit);
+    Ok(results)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use store::InMemoryVectorStore;
     use tempfile::TempDir;
 
     async fn create_test_config() -> (Config, TempDir) {
@@ -596,6 +1159,7 @@ mod tests {
                 model: None,
                 aws_region: None,
                 dimensions: None,
+                ..Default::default()
             },
             llm: crate::config::LlmConfig {
                 primary: "claude-sonnet-v4".to_string(),
@@ -603,6 +1167,8 @@ mod tests {
             },
             pgvector: crate::config::PgVectorConfig {
                 url: format!("sqlite://{}", db_path.display()),
+                collection: None,
+                index: store::VectorIndexConfig::default(),
             },
             redis: crate::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
@@ -611,6 +1177,11 @@ mod tests {
             data: crate::config::DataConfig {
                 document_dir: temp_dir.path().to_string_lossy().to_string(),
             },
+            ingestion: crate::config::IngestionConfig::default(),
+            retrieval: crate::config::RetrievalConfig::default(),
+            rerank: crate::config::RerankConfig::default(),
+            condense: crate::config::CondenseConfig::default(),
+            collections: vec![],
         };
 
         (config, temp_dir)
@@ -697,7 +1268,6 @@ mod tests {
             name: None,
         }];
 
-        use chrono::Utc;
         use store::Document;
 
         let doc = Document {
@@ -706,7 +1276,10 @@ mod tests {
             chunk_id: 0,
             content: "This is test content".to_string(),
             embedding: vec![0.1; 1024],
-            created_at: Utc::now(),
+            created_at: chrono::Utc::now(),
+            span: None,
+            parent_id: None,
+            metadata: DocumentMetadata::default(),
         };
 
         let search_results = vec![SearchResult::new(doc, 0.95)];
@@ -721,6 +1294,37 @@ mod tests {
         assert_eq!(llm_messages[1].content, "What is this about?");
     }
 
+    #[test]
+    fn should_number_context_blocks_for_citation() {
+        let results = vec![
+            SearchResult::new(
+                store::Document::new(
+                    "leave.md".to_string(),
+                    0,
+                    "Leave lasts 16 weeks.".to_string(),
+                    vec![0.1; 4],
+                ),
+                0.9,
+            ),
+            SearchResult::new(
+                store::Document::new(
+                    "vpn.md".to_string(),
+                    1,
+                    "Install the VPN client.".to_string(),
+                    vec![0.1; 4],
+                ),
+                0.7,
+            ),
+        ];
+
+        let llm_messages = AgentService::convert_to_llm_messages_static(vec![], results).unwrap();
+
+        let context = &llm_messages[0].content;
+        assert!(context.contains("[1] From leave.md: Leave lasts 16 weeks."));
+        assert!(context.contains("[2] From vpn.md: Install the VPN client."));
+        assert!(context.contains("square brackets"));
+    }
+
     #[tokio::test]
     async fn should_detect_tool_calls_with_tool_call_detector() {
         let temp_dir = TempDir::new().unwrap();
@@ -793,6 +1397,48 @@ mod tests {
                "Response should show AI service error (indicating search worked) or contain context from inserted document. Got: {}", event_content);
     }
 
+    #[tokio::test]
+    async fn should_reingest_document_when_only_its_sidecar_changes() {
+        let (config, temp_dir) = create_test_config().await;
+        let sidecar = temp_dir.path().join("leave.md.meta.yaml");
+        std::fs::write(
+            temp_dir.path().join("leave.md"),
+            "Book leave in the HR tool.\n",
+        )
+        .unwrap();
+        std::fs::write(&sidecar, "department: HR\n").unwrap();
+
+        let service = match AgentService::new(config).await {
+            Ok(s) => s,
+            Err(_) => return, // Skip if service creation fails (expected in test environment)
+        };
+        service.load_documents().await.unwrap();
+        std::fs::write(&sidecar, "department: Legal\n").unwrap();
+        service.load_documents().await.unwrap();
+
+        let query = service
+            .embeddings_client
+            .embed(vec!["leave".to_string()])
+            .await
+            .unwrap()
+            .remove(0);
+        let results = service
+            .vector_store
+            .search_similar(
+                query,
+                5,
+                &SearchOptions {
+                    filter: Some(MetadataFilter::parse("department = 'Legal'").unwrap()),
+                    ..SearchOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.file_name, "leave.md");
+    }
+
     #[tokio::test]
     async fn should_process_message_with_session_storage() {
         let (config, _temp_dir) = create_test_config().await;
@@ -934,6 +1580,7 @@ mod tests {
                 model: None,
                 aws_region: None,
                 dimensions: None,
+                ..Default::default()
             },
             llm: crate::config::LlmConfig {
                 primary: "custom-primary-model".to_string(),
@@ -941,6 +1588,8 @@ mod tests {
             },
             pgvector: crate::config::PgVectorConfig {
                 url: format!("sqlite://{}", db_path.display()),
+                collection: None,
+                index: store::VectorIndexConfig::default(),
             },
             redis: crate::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
@@ -949,6 +1598,11 @@ mod tests {
             data: crate::config::DataConfig {
                 document_dir: temp_dir.path().to_string_lossy().to_string(),
             },
+            ingestion: crate::config::IngestionConfig::default(),
+            retrieval: crate::config::RetrievalConfig::default(),
+            rerank: crate::config::RerankConfig::default(),
+            condense: crate::config::CondenseConfig::default(),
+            collections: vec![],
         };
 
         // This should fail initially because we're not using config models
@@ -986,6 +1640,7 @@ mod tests {
                 model: Some("cohere.embed-english-v3".to_string()),
                 aws_region: Some("eu-west-1".to_string()),
                 dimensions: None,
+                ..Default::default()
             },
             llm: crate::config::LlmConfig {
                 primary: "claude-sonnet-v4".to_string(),
@@ -993,6 +1648,8 @@ mod tests {
             },
             pgvector: crate::config::PgVectorConfig {
                 url: format!("sqlite://{}", db_path.display()),
+                collection: None,
+                index: store::VectorIndexConfig::default(),
             },
             redis: crate::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
@@ -1001,6 +1658,11 @@ mod tests {
             data: crate::config::DataConfig {
                 document_dir: temp_dir.path().to_string_lossy().to_string(),
             },
+            ingestion: crate::config::IngestionConfig::default(),
+            retrieval: crate::config::RetrievalConfig::default(),
+            rerank: crate::config::RerankConfig::default(),
+            condense: crate::config::CondenseConfig::default(),
+            collections: vec![],
         };
 
         // This will attempt to create BedrockCohereClient
@@ -1025,4 +1687,119 @@ mod tests {
             }
         }
     }
+
+    fn chunk_with(heading_path: Vec<&str>, context_header: Option<&str>) -> TextChunk {
+        TextChunk {
+            content: "Submit it within 30 days.".to_string(),
+            start_pos: 0,
+            end_pos: 25,
+            start_char: 0,
+            end_char: 25,
+            start_line: 1,
+            end_line: 1,
+            chunk_id: 0,
+            section_id: 0,
+            heading_path: heading_path.into_iter().map(String::from).collect(),
+            context_header: context_header.map(String::from),
+        }
+    }
+
+    #[test]
+    fn should_prepend_file_name_heading_path_and_summary() {
+        let chunk = chunk_with(vec!["Expenses", "Receipts"], None);
+
+        let text = contextual_embedding_text(
+            "expense_policy.md",
+            &chunk,
+            Some("How employees claim business expenses."),
+        );
+
+        assert_eq!(
+            text,
+            "Document: expense_policy.md\nSection: Expenses > Receipts\nSummary: How employees claim business expenses.\n\nSubmit it within 30 days."
+        );
+    }
+
+    #[test]
+    fn should_keep_code_context_header_in_contextual_text() {
+        let chunk = chunk_with(vec!["impl Loader"], Some("Module: loader\nItem: fn load()"));
+
+        let text = contextual_embedding_text("loader.rs", &chunk, None);
+
+        assert_eq!(
+            text,
+            "Document: loader.rs\nModule: loader\nItem: fn load()\n\nSubmit it within 30 days."
+        );
+    }
+
+    #[test]
+    fn should_store_multi_chunk_sections_as_parents() {
+        let chunker = TextChunker::new(ChunkConfig {
+            chunk_size: 4,
+            overlap_size: 0,
+            strategy: embeddings::ChunkStrategy::Recursive,
+            ..ChunkConfig::default()
+        });
+        let content = "# Expenses\nSubmit receipts within thirty days.\n\n# Leave\nBook it.";
+        let chunks = chunker.chunk_text(content);
+
+        let parents = parent_sections(
+            "handbook.md",
+            content,
+            &chunks,
+            1000,
+            &SpanOffset::default(),
+        );
+
+        assert_eq!(parents.len(), 1);
+        let parent = &parents[&chunks[0].section_id];
+        assert_eq!(
+            parent.content,
+            "# Expenses\nSubmit receipts within thirty days."
+        );
+        assert_eq!(parent.heading_path, vec!["Expenses".to_string()]);
+        assert!(
+            parent_sections("handbook.md", content, &chunks, 10, &SpanOffset::default()).is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_merge_results_across_collections() {
+        let default_store: Arc<dyn VectorStore> = Arc::new(InMemoryVectorStore::new());
+        let legal_store = default_store
+            .collection_store(&Collection::new("legal", "test-model", 4))
+            .await
+            .unwrap();
+        for (store, file_name) in [(&default_store, "handbook.md"), (&legal_store, "nda.md")] {
+            store
+                .insert_document(DocumentChunk::new(
+                    file_name.to_string(),
+                    0,
+                    format!("{} content", file_name),
+                    vec![0.1; 4],
+                ))
+                .await
+                .unwrap();
+        }
+        let stores = [default_store, legal_store];
+
+        let searches: Vec<QuerySearch> = stores
+            .iter()
+            .map(|store| QuerySearch {
+                store: Arc::clone(store),
+                text: "leave".to_string(),
+                embedding: vec![0.1; 4],
+            })
+            .collect();
+        let both = search_collections(&searches, 5, &SearchOptions::default(), 60.0)
+            .await
+            .unwrap();
+        let legal_only = search_collections(&searches[1..], 5, &SearchOptions::default(), 60.0)
+            .await
+            .unwrap();
+
+        assert_eq!(both.len(), 2);
+        assert_eq!(legal_only.len(), 1);
+        assert_eq!(legal_only[0].document.file_name, "nda.md");
+    }
 }
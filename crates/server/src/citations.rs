@@ -0,0 +1,233 @@
+//! Numbered sources given to the LLM and the [n] citations in its answer
+
+use serde::Serialize;
+use store::{SearchResult, SourceSpan};
+
+/// Characters of a chunk shown as its snippet
+const SNIPPET_CHARS: usize = 200;
+
+/// A context block as streamed to the client, cited by the model as `[index]`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Source {
+    /// 1-based number of the context block
+    pub index: usize,
+    pub file_name: String,
+    /// Source path, telling apart files with the same name
+    pub source: String,
+    /// The chunk that matched; its enclosing section when parents were expanded
+    pub chunk_id: usize,
+    /// Where the text given to the LLM sits in the file
+    pub span: Option<SourceSpan>,
+    pub similarity: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
+    pub snippet: String,
+}
+
+/// Sources numbered in the order the results are given to the LLM, pointing at
+/// the parent section instead of the chunk when the search expanded parents
+pub fn sources(results: &[SearchResult]) -> Vec<Source> {
+    results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| Source {
+            index: index + 1,
+            file_name: result.document.file_name.clone(),
+            source: result.document.source().to_string(),
+            chunk_id: result.document.chunk_id,
+            span: result
+                .parent
+                .as_ref()
+                .map_or(result.document.span, |parent| parent.span),
+            similarity: result.similarity,
+            rerank_score: result.rerank_score,
+            snippet: snippet(result.context()),
+        })
+        .collect()
+}
+
+fn snippet(content: &str) -> String {
+    let collapsed = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= SNIPPET_CHARS {
+        return collapsed;
+    }
+    let mut snippet: String = collapsed.chars().take(SNIPPET_CHARS).collect();
+    snippet.push('…');
+    snippet
+}
+
+/// Citation markers found in an answer, split by whether they name a source
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CitationCheck {
+    /// Distinct cited source numbers, in order of first citation
+    pub cited: Vec<usize>,
+    /// Cited numbers with no matching source
+    pub invalid: Vec<usize>,
+}
+
+/// Checks the `[n]` and `[n, m]` markers of `answer` against `source_count` sources
+pub fn check_citations(answer: &str, source_count: usize) -> CitationCheck {
+    let mut check = CitationCheck::default();
+    for number in citation_numbers(answer) {
+        let list = if (1..=source_count).contains(&number) {
+            &mut check.cited
+        } else {
+            &mut check.invalid
+        };
+        if !list.contains(&number) {
+            list.push(number);
+        }
+    }
+    check
+}
+
+fn citation_numbers(answer: &str) -> Vec<usize> {
+    let mut numbers = Vec::new();
+    let mut rest = answer;
+    while let Some(start) = rest.find('[') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let marker = &rest[..end];
+        let parsed: Option<Vec<usize>> = marker
+            .split(',')
+            .map(|number| number.trim().parse().ok())
+            .collect();
+        // Text in brackets is skipped from its opening bracket only, so a
+        // marker nested inside it is still found
+        if let Some(parsed) = parsed {
+            numbers.extend(parsed);
+            rest = &rest[end + 1..];
+        }
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::{
+        Document, DocumentChunk, DocumentMetadata, InMemoryVectorStore, ParentDocument,
+        SearchOptions, VectorStore,
+    };
+
+    #[test]
+    fn should_number_sources_with_snippets() {
+        let long_content = "word ".repeat(100);
+        let results = vec![
+            SearchResult::new(
+                Document::new(
+                    "leave.md".to_string(),
+                    3,
+                    "Parental   leave\nlasts 16 weeks.".to_string(),
+                    vec![0.1; 4],
+                ),
+                0.82,
+            ),
+            SearchResult::new(
+                Document::new("vpn.md".to_string(), 0, long_content, vec![0.1; 4]),
+                0.61,
+            ),
+        ];
+
+        let sources = sources(&results);
+
+        assert_eq!(sources[0].index, 1);
+        assert_eq!(sources[0].chunk_id, 3);
+        assert_eq!(sources[0].snippet, "Parental leave lasts 16 weeks.");
+        assert_eq!(sources[1].index, 2);
+        assert_eq!(sources[1].snippet.chars().count(), SNIPPET_CHARS + 1);
+        assert!(sources[1].snippet.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn should_point_sources_at_expanded_parent_sections() {
+        let store = InMemoryVectorStore::new();
+        let span = |start_line, end_line| SourceSpan {
+            start_byte: 0,
+            end_byte: 0,
+            start_char: 0,
+            end_char: 0,
+            start_line,
+            end_line,
+        };
+        let parent = ParentDocument::new(
+            "README.md".to_string(),
+            "# Setup\nInstall the agent.\nThen configure the VPN.".to_string(),
+            vec!["Setup".to_string()],
+        )
+        .with_span(span(1, 3));
+        let chunk = DocumentChunk::new(
+            "README.md".to_string(),
+            1,
+            "Then configure the VPN.".to_string(),
+            vec![1.0; 4],
+        )
+        .with_span(span(3, 3))
+        .with_parent(parent.id)
+        .with_metadata(DocumentMetadata {
+            source_path: Some("docs/it/README.md".to_string()),
+            ..Default::default()
+        });
+        store
+            .upsert_document("docs/it/README.md", &[parent], vec![chunk])
+            .await
+            .unwrap();
+
+        let results = store
+            .search_similar(
+                vec![1.0; 4],
+                1,
+                &SearchOptions {
+                    expand_parents: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let sources = sources(&results);
+
+        assert_eq!(sources[0].source, "docs/it/README.md");
+        assert_eq!(sources[0].chunk_id, 1);
+        assert_eq!(sources[0].span, Some(span(1, 3)));
+        assert_eq!(
+            sources[0].snippet,
+            "# Setup Install the agent. Then configure the VPN."
+        );
+    }
+
+    #[test]
+    fn should_stream_rerank_scores_with_sources() {
+        let mut reranked = SearchResult::new(
+            Document::new("leave.md".to_string(), 0, "Leave".to_string(), vec![0.1; 4]),
+            0.4,
+        );
+        reranked.rerank_score = Some(0.93);
+        let plain = SearchResult::new(
+            Document::new("vpn.md".to_string(), 0, "VPN".to_string(), vec![0.1; 4]),
+            0.3,
+        );
+
+        let json = serde_json::to_value(sources(&[reranked, plain])).unwrap();
+
+        assert!((json[0]["rerank_score"].as_f64().unwrap() - 0.93).abs() < 1e-6);
+        assert!(json[1].get("rerank_score").is_none());
+    }
+
+    #[test]
+    fn should_check_citations_against_sources() {
+        let answer =
+            "Leave lasts 16 weeks [1][3]. Contractors are excluded [1, 4]. See [policy [2]].";
+
+        let check = check_citations(answer, 3);
+
+        assert_eq!(check.cited, vec![1, 3, 2]);
+        assert_eq!(check.invalid, vec![4]);
+    }
+
+    #[test]
+    fn should_reject_citation_zero() {
+        assert_eq!(check_citations("Yes [0].", 2).invalid, vec![0]);
+    }
+}
@@ -0,0 +1,163 @@
+//! Rewrites follow-up questions into standalone search queries before retrieval
+
+use anyhow::{Context, Result};
+use llm::{BedrockClient, ChatMessage};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use store::{Message, Role};
+
+/// Condenses the latest user turn and recent history into one search query
+pub struct QueryCondenser {
+    llm_client: Arc<BedrockClient>,
+    history_turns: usize,
+    cache: Mutex<QueryCache>,
+}
+
+impl QueryCondenser {
+    pub fn new(llm_client: Arc<BedrockClient>, history_turns: usize, cache_size: usize) -> Self {
+        Self {
+            llm_client,
+            history_turns,
+            cache: Mutex::new(QueryCache::new(cache_size)),
+        }
+    }
+
+    /// Standalone query for the last user message of `conversation`; `None`
+    /// when there is no earlier turn to resolve it against
+    pub async fn condense(&self, conversation: &[Message]) -> Result<Option<String>> {
+        let Some(prompt) = condense_prompt(conversation, self.history_turns) else {
+            return Ok(None);
+        };
+        if let Some(query) = self.cache.lock().unwrap().get(&prompt) {
+            return Ok(Some(query));
+        }
+
+        let response = self
+            .llm_client
+            .complete(vec![ChatMessage::user(prompt.clone())])
+            .await
+            .context("Query condensation failed")?;
+        let query = response.split_whitespace().collect::<Vec<_>>().join(" ");
+        if query.is_empty() {
+            return Ok(None);
+        }
+
+        self.cache.lock().unwrap().insert(prompt, query.clone());
+        Ok(Some(query))
+    }
+}
+
+/// Prompt asking for a standalone query, built from the last `history_turns`
+/// user and assistant messages before the latest user message
+fn condense_prompt(conversation: &[Message], history_turns: usize) -> Option<String> {
+    let turns: Vec<&Message> = conversation
+        .iter()
+        .filter(|message| !matches!(message.role, Role::Tool))
+        .collect();
+    let latest = turns
+        .iter()
+        .rposition(|message| matches!(message.role, Role::User))?;
+    let history = &turns[latest.saturating_sub(history_turns)..latest];
+    if history.is_empty() {
+        return None;
+    }
+
+    let mut prompt = String::from(
+        "Rewrite the follow-up question as a standalone search query, resolving \
+         references to the conversation so it can be understood on its own.\n\n<conversation>\n",
+    );
+    for message in history {
+        let speaker = match message.role {
+            Role::User => "User",
+            _ => "Assistant",
+        };
+        prompt.push_str(&format!("{}: {}\n", speaker, message.content));
+    }
+    prompt.push_str(&format!(
+        "</conversation>\n\n<question>\n{}\n</question>\n\nReply with the query only.",
+        turns[latest].content
+    ));
+    Some(prompt)
+}
+
+/// Bounded map of prompts to rewritten queries, evicting the oldest entry
+struct QueryCache {
+    capacity: usize,
+    queries: HashMap<String, String>,
+    order: VecDeque<String>,
+}
+
+impl QueryCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            queries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&self, prompt: &str) -> Option<String> {
+        self.queries.get(prompt).cloned()
+    }
+
+    fn insert(&mut self, prompt: String, query: String) {
+        if self.capacity == 0 || self.queries.contains_key(&prompt) {
+            return;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.queries.remove(&oldest);
+            }
+        }
+        self.order.push_back(prompt.clone());
+        self.queries.insert(prompt, query);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: Role, content: &str) -> Message {
+        Message {
+            role,
+            content: content.to_string(),
+            name: None,
+        }
+    }
+
+    #[test]
+    fn should_skip_condensation_without_history() {
+        let conversation = vec![message(Role::User, "What is the leave policy?")];
+
+        assert!(condense_prompt(&conversation, 6).is_none());
+    }
+
+    #[test]
+    fn should_include_recent_turns_in_condense_prompt() {
+        let conversation = vec![
+            message(Role::User, "How many vacation days do employees get?"),
+            message(Role::Assistant, "Employees get 25 days."),
+            message(Role::Tool, "{\"summary\": \"ignored\"}"),
+            message(Role::User, "and for contractors?"),
+        ];
+
+        let prompt = condense_prompt(&conversation, 1).unwrap();
+
+        assert!(prompt.contains("Assistant: Employees get 25 days."));
+        assert!(!prompt.contains("How many vacation days"));
+        assert!(!prompt.contains("ignored"));
+        assert!(prompt.contains("<question>\nand for contractors?\n</question>"));
+    }
+
+    #[test]
+    fn should_evict_oldest_cached_query() {
+        let mut cache = QueryCache::new(2);
+        cache.insert("a".to_string(), "query a".to_string());
+        cache.insert("b".to_string(), "query b".to_string());
+        cache.insert("c".to_string(), "query c".to_string());
+
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("c").as_deref(), Some("query c"));
+    }
+}
@@ -1,8 +1,9 @@
-use embeddings::EmbeddingConfig;
+use embeddings::{ChunkStrategy, EmbeddingConfig};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
 use std::path::Path;
+use store::VectorIndexConfig;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Config {
@@ -11,6 +12,17 @@ pub struct Config {
     pub pgvector: PgVectorConfig,
     pub redis: RedisConfig,
     pub data: DataConfig,
+    #[serde(default)]
+    pub ingestion: IngestionConfig,
+    #[serde(default)]
+    pub retrieval: RetrievalConfig,
+    #[serde(default)]
+    pub rerank: RerankConfig,
+    #[serde(default)]
+    pub condense: CondenseConfig,
+    /// Additional named collections, each loaded from its own directory
+    #[serde(default)]
+    pub collections: Vec<CollectionConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -30,12 +42,23 @@ impl LlmConfig {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PgVectorConfig {
     pub url: String,
+    /// Collection to store and search chunks in; use a new name when switching
+    /// embedding models so documents are re-embedded into it
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Embedding index type, parameters and distance metric
+    #[serde(default)]
+    pub index: VectorIndexConfig,
 }
 
 impl PgVectorConfig {
     pub fn with_env_overrides(&self) -> Self {
         let url = env::var("PGVECTOR_URL").unwrap_or_else(|_| self.url.clone());
-        Self { url }
+        Self {
+            url,
+            collection: self.collection.clone(),
+            index: self.index.clone(),
+        }
     }
 }
 
@@ -64,6 +87,189 @@ pub struct DataConfig {
     pub document_dir: String,
 }
 
+/// A named knowledge base served next to the default collection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionConfig {
+    pub name: String,
+    pub document_dir: String,
+    /// Retrieval strategy for this collection; `[retrieval]`'s when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<RetrievalStrategy>,
+    /// Embedding provider and model for this collection's documents and queries;
+    /// `[embedding]` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<EmbeddingConfig>,
+}
+
+/// Texts searched for a question, fused with reciprocal rank fusion when several
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetrievalStrategy {
+    /// The question only
+    #[default]
+    Single,
+    /// The question and LLM-written paraphrases of it
+    MultiQuery,
+    /// The question and an LLM-written hypothetical answer passage (HyDE)
+    Hyde,
+}
+
+/// Options applied while chunking and embedding documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IngestionConfig {
+    /// Prepend the file name and section heading path to each chunk before embedding
+    pub contextual_headers: bool,
+    /// Also prepend a one-sentence LLM summary of the whole document
+    pub document_summary: bool,
+    /// Characters of the document sent to the LLM when summarising
+    pub summary_max_chars: usize,
+    /// Largest section stored as a parent of its chunks for small-to-big retrieval
+    pub parent_max_chars: usize,
+    /// How text and Markdown documents are split; source files are always split on code items
+    pub chunk_strategy: ChunkingStrategy,
+}
+
+/// Chunking strategy for text and Markdown documents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkingStrategy {
+    /// Headings, then paragraphs, sentences and words, keeping the original formatting
+    #[default]
+    Recursive,
+    /// Fixed windows of words joined by single spaces
+    WordWindow,
+}
+
+impl From<ChunkingStrategy> for ChunkStrategy {
+    fn from(strategy: ChunkingStrategy) -> Self {
+        match strategy {
+            ChunkingStrategy::Recursive => ChunkStrategy::Recursive,
+            ChunkingStrategy::WordWindow => ChunkStrategy::WordWindow,
+        }
+    }
+}
+
+impl Default for IngestionConfig {
+    fn default() -> Self {
+        Self {
+            contextual_headers: false,
+            document_summary: false,
+            summary_max_chars: 8000,
+            parent_max_chars: 4000,
+            chunk_strategy: ChunkingStrategy::Recursive,
+        }
+    }
+}
+
+/// Options applied when retrieving context for a query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetrievalConfig {
+    /// Give the LLM each hit's enclosing section instead of the matched chunk
+    pub expand_parents: bool,
+    /// Fuse full-text and vector rankings so exact terms like policy IDs are found
+    pub hybrid: bool,
+    /// Reciprocal rank fusion weight of the vector ranking
+    pub vector_weight: f32,
+    /// Reciprocal rank fusion weight of the full-text ranking
+    pub lexical_weight: f32,
+    /// Reciprocal rank fusion constant
+    pub rrf_k: f32,
+    /// Chunks given to the LLM as context
+    pub top_k: usize,
+    /// Candidates fetched from the vector store before selecting `top_k`
+    pub fetch_k: usize,
+    /// Results less similar to the query are dropped
+    pub min_similarity: f32,
+    /// Most chunks taken from one source file; 0 for no limit
+    pub max_chunks_per_file: usize,
+    /// Re-rank candidates with maximal marginal relevance to avoid near-duplicates
+    pub mmr: bool,
+    /// MMR trade-off between relevance (1.0) and diversity (0.0)
+    pub mmr_lambda: f32,
+    /// Default strategy, overridable per collection and per request
+    pub strategy: RetrievalStrategy,
+    /// Paraphrases generated by the multi-query strategy
+    pub multi_queries: usize,
+}
+
+impl Default for RetrievalConfig {
+    fn default() -> Self {
+        Self {
+            expand_parents: false,
+            hybrid: false,
+            vector_weight: 1.0,
+            lexical_weight: 1.0,
+            rrf_k: 60.0,
+            top_k: 5,
+            fetch_k: 20,
+            min_similarity: 0.0,
+            max_chunks_per_file: 0,
+            mmr: false,
+            mmr_lambda: 0.7,
+            strategy: RetrievalStrategy::Single,
+            multi_queries: 3,
+        }
+    }
+}
+
+/// Second-stage scoring of the vector search candidates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RerankConfig {
+    /// "none", "bedrock-cohere" or "llm"
+    pub provider: String,
+    /// Rerank model, or the judge model for "llm"; defaults per provider
+    pub model: Option<String>,
+    pub aws_region: Option<String>,
+    /// Candidates fetched from the vector store and reranked down to `top_k`
+    pub candidates: usize,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            provider: "none".to_string(),
+            model: None,
+            aws_region: None,
+            candidates: 20,
+        }
+    }
+}
+
+impl RerankConfig {
+    pub fn enabled(&self) -> bool {
+        self.provider != "none"
+    }
+}
+
+/// Rewriting of follow-up questions into standalone queries before retrieval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CondenseConfig {
+    pub enabled: bool,
+    /// Model for the rewrite; the primary LLM when unset
+    pub model: Option<String>,
+    pub aws_region: Option<String>,
+    /// Earlier user and assistant messages given as context
+    pub history_turns: usize,
+    /// Rewritten queries kept in memory; 0 disables the cache
+    pub cache_size: usize,
+}
+
+impl Default for CondenseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: None,
+            aws_region: None,
+            history_turns: 6,
+            cache_size: 256,
+        }
+    }
+}
+
 impl Config {
     pub fn load(path: &Path) -> anyhow::Result<Self> {
         let content = fs::read_to_string(path)?;
@@ -239,4 +445,217 @@ document_dir = "./data/faq_docs"
         );
         assert_eq!(config.embedding.aws_region.as_ref().unwrap(), "us-east-1");
     }
+
+    #[test]
+    fn should_deserialize_embedding_failover_chain() {
+        let toml_content = r#"
+[embedding]
+provider = "bedrock-cohere"
+aws_region = "eu-central-1"
+
+[[embedding.failover]]
+provider = "bedrock-titan"
+aws_region = "eu-central-1"
+dimensions = 1024
+
+[llm]
+primary = "claude-sonnet-v4"
+fallback = "claude-sonnet-v3.7"
+
+[pgvector]
+url = "postgres://localhost:5432/chatbot"
+
+[redis]
+url = "redis://localhost:6379"
+session_ttl_seconds = 86400
+
+[data]
+document_dir = "./data/faq_docs"
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(config.embedding.provider, "bedrock-cohere");
+        assert_eq!(config.embedding.failover.len(), 1);
+        assert_eq!(config.embedding.failover[0].provider, "bedrock-titan");
+        assert_eq!(config.embedding.failover[0].dimensions, Some(1024));
+    }
+
+    #[test]
+    fn should_deserialize_openai_compatible_embedding_config() {
+        let toml_content = r#"
+[embedding]
+provider = "openai-compatible"
+base_url = "http://localhost:8080"
+model = "BAAI/bge-m3"
+api_key_env = "TEI_API_KEY"
+dimensions = 1024
+batch_size = 16
+max_retries = 5
+
+[llm]
+primary = "claude-sonnet-v4"
+fallback = "claude-sonnet-v3.7"
+
+[pgvector]
+url = "postgres://localhost:5432/chatbot"
+
+[redis]
+url = "redis://localhost:6379"
+session_ttl_seconds = 86400
+
+[data]
+document_dir = "./data/faq_docs"
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(config.embedding.provider, "openai-compatible");
+        assert_eq!(
+            config.embedding.base_url.as_deref(),
+            Some("http://localhost:8080")
+        );
+        assert_eq!(config.embedding.api_key_env.as_deref(), Some("TEI_API_KEY"));
+        assert_eq!(config.embedding.batch_size, Some(16));
+        assert_eq!(config.embedding.max_retries, Some(5));
+    }
+
+    #[test]
+    fn should_default_ingestion_and_retrieval_options_when_sections_missing() {
+        let toml_content = r#"
+[embedding]
+provider = "fallback"
+
+[llm]
+primary = "claude-sonnet-v4"
+fallback = "claude-sonnet-v3.7"
+
+[pgvector]
+url = "postgres://localhost:5432/chatbot"
+
+[redis]
+url = "redis://localhost:6379"
+session_ttl_seconds = 86400
+
+[data]
+document_dir = "./data/faq_docs"
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(config.ingestion, IngestionConfig::default());
+        assert_eq!(config.retrieval, RetrievalConfig::default());
+        assert!(!config.rerank.enabled());
+        assert_eq!(config.condense, CondenseConfig::default());
+        assert!(!config.ingestion.contextual_headers);
+        assert_eq!(config.ingestion.chunk_strategy, ChunkingStrategy::Recursive);
+    }
+
+    #[test]
+    fn should_deserialize_ingestion_and_retrieval_config() {
+        let toml_content = r#"
+[embedding]
+provider = "fallback"
+
+[llm]
+primary = "claude-sonnet-v4"
+fallback = "claude-sonnet-v3.7"
+
+[pgvector]
+url = "postgres://localhost:5432/chatbot"
+
+[pgvector.index]
+type = "ivfflat"
+metric = "l2"
+lists = 20
+
+[redis]
+url = "redis://localhost:6379"
+session_ttl_seconds = 86400
+
+[data]
+document_dir = "./data/faq_docs"
+
+[ingestion]
+contextual_headers = true
+document_summary = true
+chunk_strategy = "word_window"
+
+[retrieval]
+expand_parents = true
+hybrid = true
+lexical_weight = 2.0
+top_k = 8
+mmr = true
+max_chunks_per_file = 2
+strategy = "multi_query"
+
+[rerank]
+provider = "bedrock-cohere"
+candidates = 40
+
+[condense]
+enabled = true
+model = "anthropic.claude-3-haiku-20240307-v1:0"
+
+[[collections]]
+name = "hr"
+document_dir = "./data/hr"
+
+[[collections]]
+name = "legal"
+document_dir = "./data/legal"
+strategy = "hyde"
+
+[collections.embedding]
+provider = "openai-compatible"
+model = "nomic-embed-text"
+base_url = "http://localhost:11434"
+dimensions = 768
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+
+        assert!(config.ingestion.contextual_headers);
+        assert!(config.ingestion.document_summary);
+        assert_eq!(config.ingestion.summary_max_chars, 8000);
+        assert_eq!(
+            config.ingestion.chunk_strategy,
+            ChunkingStrategy::WordWindow
+        );
+        assert!(config.retrieval.expand_parents);
+        assert!(config.retrieval.hybrid);
+        assert_eq!(config.retrieval.lexical_weight, 2.0);
+        assert_eq!(config.retrieval.vector_weight, 1.0);
+        assert_eq!(config.retrieval.top_k, 8);
+        assert!(config.retrieval.mmr);
+        assert_eq!(config.retrieval.mmr_lambda, 0.7);
+        assert_eq!(config.retrieval.max_chunks_per_file, 2);
+        assert!(config.rerank.enabled());
+        assert_eq!(config.rerank.candidates, 40);
+        assert_eq!(config.rerank.model, None);
+        assert!(config.condense.enabled);
+        assert_eq!(
+            config.condense.model.as_deref(),
+            Some("anthropic.claude-3-haiku-20240307-v1:0")
+        );
+        assert_eq!(config.condense.history_turns, 6);
+        assert_eq!(config.pgvector.index.index_type, store::IndexType::IvfFlat);
+        assert_eq!(config.pgvector.index.metric, store::DistanceMetric::L2);
+        assert_eq!(config.pgvector.index.lists, 20);
+        assert_eq!(config.collections.len(), 2);
+        assert_eq!(config.collections[1].name, "legal");
+        assert_eq!(config.collections[1].document_dir, "./data/legal");
+        assert_eq!(config.retrieval.strategy, RetrievalStrategy::MultiQuery);
+        assert_eq!(config.collections[0].strategy, None);
+        assert_eq!(
+            config.collections[1].strategy,
+            Some(RetrievalStrategy::Hyde)
+        );
+        assert_eq!(config.collections[0].embedding, None);
+        let legal_embedding = config.collections[1].embedding.as_ref().unwrap();
+        assert_eq!(legal_embedding.provider, "openai-compatible");
+        assert_eq!(legal_embedding.model.as_deref(), Some("nomic-embed-text"));
+        assert_eq!(legal_embedding.dimensions, Some(768));
+    }
 }
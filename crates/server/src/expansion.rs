@@ -0,0 +1,147 @@
+//! LLM query expansion for the multi-query and HyDE retrieval strategies
+
+use crate::config::RetrievalStrategy;
+use anyhow::{Context, Result};
+use llm::{BedrockClient, ChatMessage};
+use std::sync::Arc;
+
+/// Generates the texts searched for a query under a retrieval strategy
+pub struct QueryExpander {
+    llm_client: Arc<BedrockClient>,
+    multi_queries: usize,
+}
+
+impl QueryExpander {
+    pub fn new(llm_client: Arc<BedrockClient>, multi_queries: usize) -> Self {
+        Self {
+            llm_client,
+            multi_queries,
+        }
+    }
+
+    /// The query followed by its paraphrases (multi-query) or a hypothetical
+    /// answer passage (HyDE)
+    pub async fn expand(&self, query: &str, strategy: RetrievalStrategy) -> Result<Vec<String>> {
+        let mut texts = vec![query.to_string()];
+        match strategy {
+            RetrievalStrategy::Single => {}
+            RetrievalStrategy::MultiQuery => {
+                let response = self
+                    .complete(paraphrase_prompt(query, self.multi_queries))
+                    .await
+                    .context("Query paraphrasing failed")?;
+                texts.extend(parse_paraphrases(&response, query, self.multi_queries));
+            }
+            RetrievalStrategy::Hyde => {
+                let passage = self
+                    .complete(hyde_prompt(query))
+                    .await
+                    .context("Hypothetical document generation failed")?;
+                if !passage.trim().is_empty() {
+                    texts.push(passage.trim().to_string());
+                }
+            }
+        }
+        Ok(texts)
+    }
+
+    async fn complete(&self, prompt: String) -> Result<String> {
+        self.llm_client
+            .complete(vec![ChatMessage::user(prompt)])
+            .await
+    }
+}
+
+fn paraphrase_prompt(query: &str, count: usize) -> String {
+    format!(
+        "Write {} different search queries that ask for the same information as the \
+         question below, using other words and synonyms.\n\n<question>\n{}\n</question>\n\n\
+         Reply with one query per line and nothing else.",
+        count, query
+    )
+}
+
+fn hyde_prompt(query: &str) -> String {
+    format!(
+        "Write a short passage from an internal document that answers the question below. \
+         It does not need to be accurate; match the wording such a document would use.\n\n\
+         <question>\n{}\n</question>\n\nReply with the passage only.",
+        query
+    )
+}
+
+/// Up to `count` distinct paraphrases, one per line, without list markers
+fn parse_paraphrases(response: &str, query: &str, count: usize) -> Vec<String> {
+    let mut paraphrases: Vec<String> = Vec::new();
+    for line in response.lines() {
+        let paraphrase = strip_list_marker(line.trim()).trim();
+        if paraphrase.is_empty()
+            || paraphrase.eq_ignore_ascii_case(query)
+            || paraphrases
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(paraphrase))
+        {
+            continue;
+        }
+        paraphrases.push(paraphrase.to_string());
+    }
+    paraphrases.truncate(count);
+    paraphrases
+}
+
+/// `line` without a leading `1.`, `2)`, `-` or `*` list marker; digits that are
+/// part of the text, as in "401k rules", are kept
+fn strip_list_marker(line: &str) -> &str {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker_len = if digits > 0 && line[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else if line.starts_with(['-', '*']) {
+        1
+    } else {
+        0
+    };
+    match line[marker_len..].strip_prefix(char::is_whitespace) {
+        Some(rest) if marker_len > 0 => rest,
+        _ => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_paraphrases_without_list_markers() {
+        let response = "1. How many days off do contractors get?\n\
+                        2) Contractor vacation allowance\n\
+                        - contractor vacation allowance\n\
+                        \n\
+                        * Vacation days for contractors\n\
+                        Paid leave for freelancers\n\
+                        401k rules for contractors\n\
+                        3. 2024 leave policy\n\
+                        -5 day carry-over limit";
+
+        let paraphrases = parse_paraphrases(response, "Vacation days for contractors", 6);
+
+        assert_eq!(
+            paraphrases,
+            vec![
+                "How many days off do contractors get?",
+                "Contractor vacation allowance",
+                "Paid leave for freelancers",
+                "401k rules for contractors",
+                "2024 leave policy",
+                "-5 day carry-over limit"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_ask_for_requested_number_of_paraphrases() {
+        let prompt = paraphrase_prompt("VPN setup", 4);
+
+        assert!(prompt.starts_with("Write 4 different search queries"));
+        assert!(prompt.contains("<question>\nVPN setup\n</question>"));
+    }
+}
@@ -1,5 +1,11 @@
 pub mod agent;
+pub mod citations;
+pub mod condense;
 pub mod config;
 pub mod errors;
+pub mod expansion;
+pub mod metadata;
 pub mod models;
+pub mod rerank;
+pub mod retrieval;
 pub mod sse;
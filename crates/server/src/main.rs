@@ -12,9 +12,15 @@ use std::sync::Arc;
 use server as _;
 
 pub mod agent;
+pub mod citations;
+pub mod condense;
 pub mod config;
 pub mod errors;
+pub mod expansion;
+pub mod metadata;
 pub mod models;
+pub mod rerank;
+pub mod retrieval;
 pub mod sse;
 
 use models::PredictStreamRequest;
@@ -42,8 +48,14 @@ async fn predict_stream_with_agent(
     use errors::AgentError;
     use sse::create_error_event;
 
+    let options = agent::QueryOptions {
+        filter: request.filter,
+        collections: request.collections,
+        strategy: request.strategy,
+    };
+
     match agent_service
-        .process_message(request.session_id, request.messages)
+        .process_message_with_options(request.session_id, request.messages, options)
         .await
     {
         Ok(events) => create_sse_stream(events),
@@ -121,6 +133,17 @@ async fn main() -> anyhow::Result<()> {
     } else {
         info!("Documents loaded successfully");
     }
+    match agent_service.collection_stats().await {
+        Ok(stats) => {
+            for collection in stats {
+                info!(
+                    "Collection '{}': {} chunks from {} files",
+                    collection.name, collection.documents, collection.files
+                );
+            }
+        }
+        Err(e) => warn!("Failed to read collection stats: {}", e),
+    }
 
     // Create app with AgentService
     let app = create_app_with_state(agent_service);
@@ -161,6 +184,7 @@ fn create_development_config() -> crate::config::Config {
             model: None,
             aws_region: None,
             dimensions: None,
+            ..Default::default()
         },
         llm: crate::config::LlmConfig {
             primary: "claude-sonnet-v4".to_string(),
@@ -168,6 +192,8 @@ fn create_development_config() -> crate::config::Config {
         },
         pgvector: crate::config::PgVectorConfig {
             url: "sqlite://./dev.db".to_string(),
+            collection: None,
+            index: store::VectorIndexConfig::default(),
         },
         redis: crate::config::RedisConfig {
             url: "redis://localhost:6379".to_string(),
@@ -176,6 +202,11 @@ fn create_development_config() -> crate::config::Config {
         data: crate::config::DataConfig {
             document_dir: "./data".to_string(),
         },
+        ingestion: crate::config::IngestionConfig::default(),
+        retrieval: crate::config::RetrievalConfig::default(),
+        rerank: crate::config::RerankConfig::default(),
+        condense: crate::config::CondenseConfig::default(),
+        collections: vec![],
     }
 }
 
@@ -244,6 +275,9 @@ mod tests {
                 content: "How do I submit expenses?".to_string(),
                 name: None,
             }],
+            filter: None,
+            collections: None,
+            strategy: None,
         };
 
         let json_body = serde_json::to_string(&request_body).unwrap();
@@ -293,6 +327,7 @@ mod tests {
                 model: None,
                 aws_region: None,
                 dimensions: None,
+                ..Default::default()
             },
             llm: crate::config::LlmConfig {
                 primary: "claude-sonnet-v4".to_string(),
@@ -300,6 +335,8 @@ mod tests {
             },
             pgvector: crate::config::PgVectorConfig {
                 url: format!("sqlite://{}", db_path.display()),
+                collection: None,
+                index: store::VectorIndexConfig::default(),
             },
             redis: crate::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
@@ -308,6 +345,11 @@ mod tests {
             data: crate::config::DataConfig {
                 document_dir: temp_dir.path().to_string_lossy().to_string(),
             },
+            ingestion: crate::config::IngestionConfig::default(),
+            retrieval: crate::config::RetrievalConfig::default(),
+            rerank: crate::config::RerankConfig::default(),
+            condense: crate::config::CondenseConfig::default(),
+            collections: vec![],
         };
 
         // Test the actual AgentService functionality
@@ -324,6 +366,9 @@ mod tests {
                         content: "What are the company policies?".to_string(),
                         name: None,
                     }],
+                    filter: None,
+                    collections: None,
+                    strategy: None,
                 };
 
                 let json_body = serde_json::to_string(&request_body).unwrap();
@@ -406,6 +451,7 @@ mod tests {
                 model: None,
                 aws_region: None,
                 dimensions: None,
+                ..Default::default()
             },
             llm: crate::config::LlmConfig {
                 primary: "claude-sonnet-v4".to_string(),
@@ -413,6 +459,8 @@ mod tests {
             },
             pgvector: crate::config::PgVectorConfig {
                 url: format!("sqlite://{}", db_path.display()),
+                collection: None,
+                index: store::VectorIndexConfig::default(),
             },
             redis: crate::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
@@ -421,6 +469,11 @@ mod tests {
             data: crate::config::DataConfig {
                 document_dir: data_dir.to_string_lossy().to_string(),
             },
+            ingestion: crate::config::IngestionConfig::default(),
+            retrieval: crate::config::RetrievalConfig::default(),
+            rerank: crate::config::RerankConfig::default(),
+            condense: crate::config::CondenseConfig::default(),
+            collections: vec![],
         };
 
         match AgentService::new(config).await {
@@ -436,6 +489,9 @@ mod tests {
                         content: "Please summarize the test_document.txt file".to_string(),
                         name: None,
                     }],
+                    filter: None,
+                    collections: None,
+                    strategy: None,
                 };
 
                 let json_body = serde_json::to_string(&request_body).unwrap();
@@ -0,0 +1,330 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use store::DocumentMetadata;
+
+/// Sidecar file extensions checked next to a document, e.g. `hr_policy.md.meta.yaml`
+const SIDECAR_SUFFIXES: [&str; 2] = [".meta.yaml", ".meta.yml"];
+
+/// Keys filled in by ingestion; the same keys in sidecars or front matter are ignored
+const RESERVED_KEYS: [&str; 5] = [
+    "source_path",
+    "language",
+    "mtime",
+    "checksum",
+    "heading_path",
+];
+
+/// Builds a document's metadata from its file (source path, language, mtime),
+/// then applies a sidecar YAML file and finally YAML front matter, later sources
+/// overriding earlier ones apart from the [`RESERVED_KEYS`]. Sidecars and front matter that fail to parse are
+/// skipped with a warning so one bad file does not stop ingestion. The checksum
+/// covers the content, the resolved metadata and the ingestion `settings`, so a
+/// file is re-ingested when any of them changes.
+pub async fn document_metadata(path: &Path, content: &str, settings: &str) -> DocumentMetadata {
+    let base = DocumentMetadata {
+        source_path: Some(path.display().to_string()),
+        language: language_for(path).map(str::to_string),
+        mtime: tokio::fs::metadata(path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::<Utc>::from),
+        ..Default::default()
+    };
+    let mut fields = match base.to_value() {
+        Value::Object(fields) => fields,
+        _ => Map::new(),
+    };
+
+    for sidecar in sidecar_paths(path) {
+        if let Ok(yaml) = tokio::fs::read_to_string(&sidecar).await {
+            match parse_yaml_mapping(&yaml) {
+                Ok(sidecar_fields) => {
+                    fields.extend(without_reserved_keys(sidecar_fields, &sidecar))
+                }
+                Err(e) => warn!(
+                    "Ignoring invalid metadata sidecar {}: {}",
+                    sidecar.display(),
+                    e
+                ),
+            }
+            break;
+        }
+    }
+
+    match front_matter(path, content) {
+        Some((Ok(front_matter_fields), _)) => {
+            fields.extend(without_reserved_keys(front_matter_fields, path))
+        }
+        Some((Err(e), _)) => warn!(
+            "Ignoring front matter in {}, keeping it as content: {}",
+            path.display(),
+            e
+        ),
+        None => {}
+    }
+
+    // Allow `tags: policy` as shorthand for a single tag
+    if let Some(Value::String(tag)) = fields.get("tags").cloned() {
+        fields.insert("tags".to_string(), Value::Array(vec![Value::String(tag)]));
+    }
+
+    let mut metadata = match serde_json::from_value(Value::Object(fields)) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            warn!(
+                "Invalid metadata for {}, using file metadata only: {}",
+                path.display(),
+                e
+            );
+            base
+        }
+    };
+    metadata.checksum = Some(ingestion_checksum(content, &metadata, settings));
+    metadata
+}
+
+/// `fields` from the YAML in `origin` without the keys ingestion fills in
+fn without_reserved_keys(mut fields: Map<String, Value>, origin: &Path) -> Map<String, Value> {
+    for key in RESERVED_KEYS {
+        if fields.remove(key).is_some() {
+            warn!(
+                "Ignoring reserved metadata key '{}' in {}",
+                key,
+                origin.display()
+            );
+        }
+    }
+    fields
+}
+
+/// Hash of the content, the metadata apart from its checksum and mtime, and the
+/// ingestion settings
+fn ingestion_checksum(content: &str, metadata: &DocumentMetadata, settings: &str) -> String {
+    let mut fields = metadata.to_value();
+    if let Value::Object(fields) = &mut fields {
+        fields.remove("checksum");
+        fields.remove("mtime");
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    hasher.update([0]);
+    hasher.update(fields.to_string().as_bytes());
+    hasher.update([0]);
+    hasher.update(settings.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Byte length of the YAML front matter block (`---` ... `---`) at the start of
+/// a Markdown file, or 0 when there is none or it is not a YAML mapping, in which
+/// case the block is ordinary content (e.g. an opening horizontal rule)
+pub fn front_matter_len(path: &Path, content: &str) -> usize {
+    match front_matter(path, content) {
+        Some((Ok(_), len)) => len,
+        _ => 0,
+    }
+}
+
+/// Parsed front matter of a Markdown file and the byte length of its block
+fn front_matter(path: &Path, content: &str) -> Option<(Result<Map<String, Value>>, usize)> {
+    if path.extension().and_then(|s| s.to_str()) != Some("md") {
+        return None;
+    }
+    let (yaml, len) = split_front_matter(content)?;
+    Some((parse_yaml_mapping(yaml), len))
+}
+
+/// Returns the YAML between the front matter fences and the byte length of the whole block
+fn split_front_matter(content: &str) -> Option<(&str, usize)> {
+    let first_line = content.split_inclusive('\n').next()?;
+    if first_line.trim_end() != "---" {
+        return None;
+    }
+
+    let mut offset = first_line.len();
+    for line in content[first_line.len()..].split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some((&content[first_line.len()..offset], offset + line.len()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn parse_yaml_mapping(yaml: &str) -> Result<Map<String, Value>> {
+    if yaml.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_yaml::from_str::<Value>(yaml)? {
+        Value::Object(fields) => Ok(fields),
+        Value::Null => Ok(Map::new()),
+        other => anyhow::bail!("expected a mapping, found {}", other),
+    }
+}
+
+fn sidecar_paths(path: &Path) -> Vec<PathBuf> {
+    SIDECAR_SUFFIXES
+        .iter()
+        .map(|suffix| {
+            let mut sidecar = path.as_os_str().to_owned();
+            sidecar.push(suffix);
+            PathBuf::from(sidecar)
+        })
+        .collect()
+}
+
+fn language_for(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "md" => Some("markdown"),
+        "txt" => Some("text"),
+        "rs" => Some("rust"),
+        "py" => Some("python"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn should_measure_front_matter_block() {
+        let path = Path::new("leave.md");
+        let content = "---\ndepartment: HR\n---\n# Leave policy\n";
+
+        assert_eq!(
+            &content[front_matter_len(path, content)..],
+            "# Leave policy\n"
+        );
+        assert_eq!(front_matter_len(path, "# No front matter\n---\n"), 0);
+        assert_eq!(front_matter_len(path, "---\nunterminated: true\n"), 0);
+        // Only Markdown files have front matter
+        assert_eq!(front_matter_len(Path::new("leave.txt"), content), 0);
+    }
+
+    #[tokio::test]
+    async fn should_ignore_reserved_keys_from_sidecar_and_front_matter() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("leave.md");
+        let content = "---\nchecksum: pinned\nlanguage: klingon\n---\nBook leave.\n";
+        std::fs::write(&path, content).unwrap();
+        std::fs::write(
+            dir.path().join("leave.md.meta.yaml"),
+            "source_path: elsewhere.md\nmtime: 2020-01-01T00:00:00Z\ndepartment: HR\n",
+        )
+        .unwrap();
+
+        let metadata = document_metadata(&path, content, "").await;
+
+        assert_eq!(
+            metadata.source_path.as_deref(),
+            Some(path.display().to_string().as_str())
+        );
+        assert_eq!(metadata.language.as_deref(), Some("markdown"));
+        assert_ne!(metadata.checksum.as_deref(), Some("pinned"));
+        assert_ne!(
+            metadata.mtime,
+            Some("2020-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert_eq!(metadata.department.as_deref(), Some("HR"));
+        assert!(metadata.extra.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_change_checksum_with_sidecar_and_settings() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("leave.md");
+        let sidecar = dir.path().join("leave.md.meta.yaml");
+        let content = "Book leave in the HR tool.\n";
+        std::fs::write(&path, content).unwrap();
+        std::fs::write(&sidecar, "department: HR\n").unwrap();
+
+        let checksum = |settings: &'static str| {
+            let path = path.clone();
+            async move {
+                document_metadata(&path, content, settings)
+                    .await
+                    .checksum
+                    .unwrap()
+            }
+        };
+        let original = checksum("chunk_size=500").await;
+        assert_eq!(checksum("chunk_size=500").await, original);
+        assert_ne!(checksum("chunk_size=300").await, original);
+
+        std::fs::write(&sidecar, "department: Legal\n").unwrap();
+        assert_ne!(checksum("chunk_size=500").await, original);
+    }
+
+    #[tokio::test]
+    async fn should_keep_horizontal_rule_and_ignore_malformed_sidecar() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("handbook.md");
+        let content = "---\nWelcome to the handbook.\n\n---\n# Leave\n";
+        std::fs::write(&path, content).unwrap();
+        std::fs::write(
+            dir.path().join("handbook.md.meta.yaml"),
+            "department: [unclosed\n",
+        )
+        .unwrap();
+
+        let metadata = document_metadata(&path, content, "").await;
+
+        // The text between the rules is not YAML front matter and stays content
+        assert_eq!(front_matter_len(&path, content), 0);
+        assert_eq!(metadata.department, None);
+        assert_eq!(metadata.language.as_deref(), Some("markdown"));
+        assert_eq!(metadata.checksum.as_ref().map(String::len), Some(64));
+        assert!(metadata.extra.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_combine_file_sidecar_and_front_matter_metadata() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("leave.md");
+        let content = "---\ntags: policy\nreviewed: true\n---\nBook leave in the HR tool.\n";
+        std::fs::write(&path, content).unwrap();
+        std::fs::write(
+            dir.path().join("leave.md.meta.yaml"),
+            "department: HR\ntags: [draft]\n",
+        )
+        .unwrap();
+
+        let metadata = document_metadata(&path, content, "").await;
+
+        assert_eq!(metadata.department.as_deref(), Some("HR"));
+        // Front matter overrides the sidecar
+        assert_eq!(metadata.tags, vec!["policy".to_string()]);
+        assert_eq!(metadata.language.as_deref(), Some("markdown"));
+        assert_eq!(
+            metadata.source_path.as_deref(),
+            Some(path.display().to_string().as_str())
+        );
+        assert_eq!(metadata.checksum.as_ref().map(String::len), Some(64));
+        assert!(metadata.mtime.is_some());
+        assert_eq!(metadata.extra["reviewed"], Value::Bool(true));
+    }
+
+    #[tokio::test]
+    async fn should_fall_back_to_file_metadata_for_non_mapping_sidecar() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "notes").unwrap();
+        std::fs::write(
+            dir.path().join("notes.txt.meta.yml"),
+            "- not\n- a mapping\n",
+        )
+        .unwrap();
+
+        let metadata = document_metadata(&path, "notes", "").await;
+
+        assert_eq!(metadata.language.as_deref(), Some("text"));
+        assert!(metadata.extra.is_empty());
+    }
+}
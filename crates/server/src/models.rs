@@ -1,3 +1,4 @@
+use crate::config::RetrievalStrategy;
 use serde::{Deserialize, Serialize};
 use store::Message;
 use uuid::Uuid;
@@ -6,6 +7,15 @@ use uuid::Uuid;
 pub struct PredictStreamRequest {
     pub session_id: Uuid,
     pub messages: Vec<Message>,
+    /// Metadata filter for retrieval, e.g. `department = 'HR' AND tags ? 'policy'`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// Collections to search; all served collections when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collections: Option<Vec<String>>,
+    /// Retrieval strategy overriding the configured ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<RetrievalStrategy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +37,9 @@ mod tests {
                 content: "Hello".to_string(),
                 name: None,
             }],
+            filter: None,
+            collections: None,
+            strategy: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -59,6 +72,62 @@ mod tests {
         assert_eq!(request.messages[0].role, Role::User);
         assert_eq!(request.messages[0].content, "How do I submit expenses?");
         assert_eq!(request.messages[0].name, None);
+        assert_eq!(request.filter, None);
+    }
+
+    #[test]
+    fn should_deserialize_predict_stream_request_with_filter() {
+        let json = format!(
+            r#"{{
+            "session_id": "{}",
+            "messages": [],
+            "filter": "department = 'HR' AND tags ? 'policy'"
+        }}"#,
+            Uuid::new_v4()
+        );
+
+        let request: PredictStreamRequest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            request.filter.as_deref(),
+            Some("department = 'HR' AND tags ? 'policy'")
+        );
+        assert_eq!(request.collections, None);
+    }
+
+    #[test]
+    fn should_deserialize_predict_stream_request_with_collections() {
+        let json = format!(
+            r#"{{
+            "session_id": "{}",
+            "messages": [],
+            "collections": ["hr", "legal"]
+        }}"#,
+            Uuid::new_v4()
+        );
+
+        let request: PredictStreamRequest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            request.collections,
+            Some(vec!["hr".to_string(), "legal".to_string()])
+        );
+    }
+
+    #[test]
+    fn should_deserialize_predict_stream_request_with_strategy() {
+        let json = format!(
+            r#"{{
+            "session_id": "{}",
+            "messages": [],
+            "strategy": "hyde"
+        }}"#,
+            Uuid::new_v4()
+        );
+
+        let request: PredictStreamRequest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(request.strategy, Some(RetrievalStrategy::Hyde));
     }
 
     #[test]
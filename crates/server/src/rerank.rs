@@ -0,0 +1,245 @@
+//! Reranking of vector search candidates before the context is selected
+
+use crate::config::RerankConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use embeddings::{BedrockRerankClient, BedrockRerankConfig};
+use llm::{BedrockClient, ChatMessage};
+use log::info;
+use std::sync::Arc;
+use store::SearchResult;
+
+/// Reranker names accepted in `RerankConfig.provider`
+pub const SUPPORTED_RERANKERS: [&str; 3] = ["none", "bedrock-cohere", "llm"];
+
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Reorders `results` most relevant to `query` first, setting their rerank score
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>>;
+}
+
+/// Keeps the vector search order
+pub struct NoopReranker;
+
+#[async_trait]
+impl Reranker for NoopReranker {
+    async fn rerank(&self, _query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        Ok(results)
+    }
+}
+
+/// Cohere Rerank on Bedrock, scoring each candidate's text against the query
+pub struct CohereReranker {
+    client: BedrockRerankClient,
+}
+
+impl CohereReranker {
+    pub fn new(client: BedrockRerankClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Reranker for CohereReranker {
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        let documents: Vec<String> = results
+            .iter()
+            .map(|result| result.context().to_string())
+            .collect();
+        let ranked = self.client.rerank(query, &documents).await?;
+
+        let mut scores = vec![0.0; results.len()];
+        for result in ranked {
+            if let Some(score) = scores.get_mut(result.index) {
+                *score = result.relevance_score;
+            }
+        }
+        Ok(apply_scores(results, scores))
+    }
+}
+
+/// Asks the LLM to grade each candidate's relevance from 0 to 10
+pub struct LlmJudgeReranker {
+    llm_client: Arc<BedrockClient>,
+}
+
+impl LlmJudgeReranker {
+    pub fn new(llm_client: Arc<BedrockClient>) -> Self {
+        Self { llm_client }
+    }
+}
+
+#[async_trait]
+impl Reranker for LlmJudgeReranker {
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        if results.is_empty() {
+            return Ok(results);
+        }
+
+        let response = self
+            .llm_client
+            .complete(vec![ChatMessage::user(judge_prompt(query, &results))])
+            .await
+            .context("LLM reranking failed")?;
+        let scores = parse_judge_scores(&response, results.len())?;
+        Ok(apply_scores(results, scores))
+    }
+}
+
+fn judge_prompt(query: &str, results: &[SearchResult]) -> String {
+    let mut prompt = format!(
+        "Rate how relevant each passage is for answering the question, from 0 (irrelevant) \
+         to 10 (answers it directly).\n\n<question>\n{}\n</question>\n\n",
+        query
+    );
+    for (index, result) in results.iter().enumerate() {
+        prompt.push_str(&format!(
+            "<passage id=\"{}\">\n{}\n</passage>\n\n",
+            index + 1,
+            result.context()
+        ));
+    }
+    prompt.push_str(&format!(
+        "Reply with a JSON array of {} numbers, one score per passage in order, and nothing else.",
+        results.len()
+    ));
+    prompt
+}
+
+/// Scores from the judge's reply, scaled to 0..1
+fn parse_judge_scores(response: &str, count: usize) -> Result<Vec<f32>> {
+    let json = response
+        .find('[')
+        .zip(response.rfind(']'))
+        .map(|(start, end)| &response[start..=end])
+        .ok_or_else(|| anyhow::anyhow!("LLM reranker reply has no score array: {}", response))?;
+    let scores: Vec<f32> =
+        serde_json::from_str(json).context("LLM reranker reply is not a score array")?;
+    if scores.len() != count {
+        anyhow::bail!(
+            "LLM reranker returned {} scores for {} passages",
+            scores.len(),
+            count
+        );
+    }
+    Ok(scores
+        .into_iter()
+        .map(|score| score.clamp(0.0, 10.0) / 10.0)
+        .collect())
+}
+
+/// Sets each result's rerank score and sorts by it; ties keep the search order
+fn apply_scores(results: Vec<SearchResult>, scores: Vec<f32>) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = results
+        .into_iter()
+        .zip(scores)
+        .map(|(mut result, score)| {
+            result.rerank_score = Some(score);
+            result
+        })
+        .collect();
+    results.sort_by(|a, b| b.relevance().total_cmp(&a.relevance()));
+    results
+}
+
+/// Builds the configured reranker; the LLM judge uses `llm_client` unless a model is set
+pub async fn create_reranker(
+    config: &RerankConfig,
+    llm_client: Arc<BedrockClient>,
+) -> Result<Arc<dyn Reranker>> {
+    let reranker: Arc<dyn Reranker> = match config.provider.as_str() {
+        "none" => return Ok(Arc::new(NoopReranker)),
+        "bedrock-cohere" => {
+            let defaults = BedrockRerankConfig::default();
+            let client = BedrockRerankClient::new(BedrockRerankConfig {
+                model_id: config.model.clone().unwrap_or(defaults.model_id),
+                aws_region: config.aws_region.clone().unwrap_or(defaults.aws_region),
+            })
+            .await?;
+            Arc::new(CohereReranker::new(client))
+        }
+        "llm" => {
+            let llm_client = crate::agent::llm_client_for_model(
+                config.model.as_deref(),
+                config.aws_region.as_deref(),
+                llm_client,
+            )
+            .await?;
+            Arc::new(LlmJudgeReranker::new(llm_client))
+        }
+        unknown => anyhow::bail!(
+            "Unknown reranker '{}'. Supported rerankers: {}",
+            unknown,
+            SUPPORTED_RERANKERS.join(", ")
+        ),
+    };
+    info!(
+        "Reranking {} candidates with '{}'",
+        config.candidates, config.provider
+    );
+    Ok(reranker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::Document;
+
+    fn results(contents: &[&str]) -> Vec<SearchResult> {
+        contents
+            .iter()
+            .enumerate()
+            .map(|(chunk_id, content)| {
+                SearchResult::new(
+                    Document::new(
+                        "faq.md".to_string(),
+                        chunk_id,
+                        content.to_string(),
+                        vec![0.1; 4],
+                    ),
+                    0.9 - chunk_id as f32 * 0.1,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_judge_scores_from_reply() {
+        let scores = parse_judge_scores("Scores:\n[2, 10, 7.5]", 3).unwrap();
+
+        assert_eq!(scores, vec![0.2, 1.0, 0.75]);
+        assert!(parse_judge_scores("[2, 10]", 3).is_err());
+        assert!(parse_judge_scores("I cannot rate these.", 3).is_err());
+    }
+
+    #[test]
+    fn should_sort_results_by_rerank_score() {
+        let reranked = apply_scores(results(&["a", "b", "c"]), vec![0.1, 0.8, 0.5]);
+
+        let contents: Vec<&str> = reranked
+            .iter()
+            .map(|r| r.document.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["b", "c", "a"]);
+        assert_eq!(reranked[0].rerank_score, Some(0.8));
+    }
+
+    #[test]
+    fn should_number_passages_in_judge_prompt() {
+        let prompt = judge_prompt("What is the leave policy?", &results(&["Leave", "VPN"]));
+
+        assert!(prompt.contains("<passage id=\"2\">\nVPN\n</passage>"));
+        assert!(prompt.contains("JSON array of 2 numbers"));
+    }
+
+    #[tokio::test]
+    async fn should_keep_order_without_reranker() {
+        let reranked = NoopReranker
+            .rerank("query", results(&["a", "b"]))
+            .await
+            .unwrap();
+
+        assert_eq!(reranked[0].document.content, "a");
+        assert!(reranked.iter().all(|r| r.rerank_score.is_none()));
+    }
+}
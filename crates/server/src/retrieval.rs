@@ -0,0 +1,176 @@
+//! Selection of the context chunks among a store's search results, shared by
+//! every vector store backend
+
+use crate::config::RetrievalConfig;
+use std::collections::HashMap;
+use store::SearchResult;
+
+/// Number of results to request from the stores so selection has enough candidates
+pub fn candidate_count(config: &RetrievalConfig) -> usize {
+    config.fetch_k.max(config.top_k)
+}
+
+/// Picks up to `top_k` results: drops those below `min_similarity`, caps the
+/// chunks taken from one file, and with `mmr` trades relevance against
+/// similarity to the chunks already picked. Expects results best first.
+pub fn select(results: Vec<SearchResult>, config: &RetrievalConfig) -> Vec<SearchResult> {
+    let mut candidates: Vec<SearchResult> = results
+        .into_iter()
+        .filter(|result| result.similarity >= config.min_similarity)
+        .collect();
+    let mut selected: Vec<SearchResult> = Vec::new();
+    let mut per_file: HashMap<String, usize> = HashMap::new();
+
+    while selected.len() < config.top_k && !candidates.is_empty() {
+        candidates.retain(|result| {
+            config.max_chunks_per_file == 0
+                || per_file
+                    .get(result.document.source())
+                    .is_none_or(|&count| count < config.max_chunks_per_file)
+        });
+
+        let best = if config.mmr {
+            candidates
+                .iter()
+                .map(|candidate| mmr_score(candidate, &selected, config.mmr_lambda))
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(index, _)| index)
+        } else {
+            (!candidates.is_empty()).then_some(0)
+        };
+        let Some(best) = best else {
+            break;
+        };
+
+        let result = candidates.remove(best);
+        *per_file
+            .entry(result.document.source().to_string())
+            .or_default() += 1;
+        selected.push(result);
+    }
+
+    selected
+}
+
+/// `lambda * relevance - (1 - lambda) * redundancy`, where relevance is the
+/// rerank score when set and redundancy is the highest cosine similarity to an
+/// already selected chunk
+fn mmr_score(candidate: &SearchResult, selected: &[SearchResult], lambda: f32) -> f32 {
+    let redundancy = selected
+        .iter()
+        .map(|picked| cosine_similarity(&candidate.document.embedding, &picked.document.embedding))
+        .fold(0.0, f32::max);
+    lambda * candidate.relevance() - (1.0 - lambda) * redundancy
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::Document;
+
+    fn result(
+        file_name: &str,
+        chunk_id: usize,
+        embedding: Vec<f32>,
+        similarity: f32,
+    ) -> SearchResult {
+        SearchResult::new(
+            Document::new(
+                file_name.to_string(),
+                chunk_id,
+                format!("{} {}", file_name, chunk_id),
+                embedding,
+            ),
+            similarity,
+        )
+    }
+
+    fn chunk_ids(results: &[SearchResult]) -> Vec<(String, usize)> {
+        results
+            .iter()
+            .map(|r| (r.document.file_name.clone(), r.document.chunk_id))
+            .collect()
+    }
+
+    #[test]
+    fn should_apply_top_k_threshold_and_file_cap() {
+        let config = RetrievalConfig {
+            top_k: 3,
+            min_similarity: 0.5,
+            max_chunks_per_file: 2,
+            ..RetrievalConfig::default()
+        };
+        let results = vec![
+            result("a.md", 0, vec![1.0, 0.0], 0.9),
+            result("a.md", 1, vec![1.0, 0.0], 0.85),
+            result("a.md", 2, vec![1.0, 0.0], 0.8),
+            result("b.md", 0, vec![0.0, 1.0], 0.7),
+            result("c.md", 0, vec![0.0, 1.0], 0.4),
+        ];
+
+        let selected = select(results, &config);
+
+        assert_eq!(
+            chunk_ids(&selected),
+            vec![
+                ("a.md".to_string(), 0),
+                ("a.md".to_string(), 1),
+                ("b.md".to_string(), 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_prefer_diverse_chunks_with_mmr() {
+        let config = RetrievalConfig {
+            top_k: 2,
+            mmr: true,
+            mmr_lambda: 0.5,
+            ..RetrievalConfig::default()
+        };
+        // Two overlapping windows of one passage and a distinct, slightly less relevant one
+        let results = vec![
+            result("a.md", 0, vec![1.0, 0.0], 0.9),
+            result("a.md", 1, vec![0.99, 0.1], 0.88),
+            result("b.md", 0, vec![0.0, 1.0], 0.8),
+        ];
+
+        let plain = select(
+            results.clone(),
+            &RetrievalConfig {
+                mmr: false,
+                ..config.clone()
+            },
+        );
+        let diverse = select(results, &config);
+
+        assert_eq!(chunk_ids(&plain)[1], ("a.md".to_string(), 1));
+        assert_eq!(
+            chunk_ids(&diverse),
+            vec![("a.md".to_string(), 0), ("b.md".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn should_fetch_at_least_top_k_candidates() {
+        let config = RetrievalConfig {
+            top_k: 30,
+            fetch_k: 20,
+            ..RetrievalConfig::default()
+        };
+
+        assert_eq!(candidate_count(&config), 30);
+        assert_eq!(candidate_count(&RetrievalConfig::default()), 20);
+    }
+}
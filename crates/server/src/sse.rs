@@ -41,6 +41,57 @@ pub fn create_assistant_output_event(content: &str) -> Event {
         .data(data.to_string())
 }
 
+/// The standalone query used for retrieval in place of the user's question
+pub fn create_query_rewrite_event(original: &str, rewritten: &str) -> Event {
+    let data = serde_json::json!({
+        "original": original,
+        "rewritten": rewritten
+    });
+
+    Event::default()
+        .event("query_rewrite")
+        .data(data.to_string())
+}
+
+/// Rerank scores of the chunks kept after reranking, in their new order
+pub fn create_rerank_event(results: &[store::SearchResult]) -> Event {
+    let results: Vec<Value> = results
+        .iter()
+        .map(|result| {
+            serde_json::json!({
+                "file_name": result.document.file_name,
+                "chunk_id": result.document.chunk_id,
+                "similarity": result.similarity,
+                "rerank_score": result.rerank_score
+            })
+        })
+        .collect();
+    let data = serde_json::json!({
+        "results": results
+    });
+
+    Event::default().event("rerank").data(data.to_string())
+}
+
+/// The numbered context blocks given to the LLM, sent before generation
+pub fn create_sources_event(sources: &[crate::citations::Source]) -> Event {
+    let data = serde_json::json!({
+        "sources": sources
+    });
+
+    Event::default().event("sources").data(data.to_string())
+}
+
+/// The answer's citation markers, checked against the sources
+pub fn create_citations_event(check: &crate::citations::CitationCheck) -> Event {
+    let data = serde_json::json!({
+        "cited": check.cited,
+        "invalid": check.invalid
+    });
+
+    Event::default().event("citations").data(data.to_string())
+}
+
 pub fn create_streaming_content_event(content: &str) -> Event {
     let data = serde_json::json!({
         "content": content,
@@ -111,6 +162,51 @@ mod tests {
         assert!(event_str.contains("Test summary"));
     }
 
+    #[tokio::test]
+    async fn should_create_query_rewrite_event() {
+        let event =
+            create_query_rewrite_event("and for contractors?", "vacation days for contractors");
+
+        let event_str = format!("{:?}", event);
+        assert!(event_str.contains("query_rewrite"));
+        assert!(event_str.contains("and for contractors?"));
+        assert!(event_str.contains("vacation days for contractors"));
+    }
+
+    #[tokio::test]
+    async fn should_create_rerank_event() {
+        let document = store::Document::new("leave.md".to_string(), 3, "text".into(), vec![]);
+        let mut result = store::SearchResult::new(document, 0.42);
+        result.rerank_score = Some(0.91);
+
+        let event = create_rerank_event(&[result]);
+
+        let event_str = format!("{:?}", event);
+        assert!(event_str.contains("rerank"));
+        assert!(event_str.contains("leave.md"));
+        assert!(event_str.contains("0.91"));
+    }
+
+    #[tokio::test]
+    async fn should_create_sources_event() {
+        let sources = vec![crate::citations::Source {
+            index: 1,
+            file_name: "leave.md".to_string(),
+            source: "docs/hr/leave.md".to_string(),
+            chunk_id: 2,
+            span: None,
+            similarity: 0.5,
+            rerank_score: Some(0.75),
+            snippet: "Parental leave lasts 16 weeks.".to_string(),
+        }];
+
+        let event_str = format!("{:?}", create_sources_event(&sources));
+        assert!(event_str.contains("sources"));
+        assert!(event_str.contains("docs/hr/leave.md"));
+        assert!(event_str.contains("rerank_score"));
+        assert!(event_str.contains("Parental leave lasts 16 weeks."));
+    }
+
     #[tokio::test]
     async fn should_create_assistant_output_event() {
         let event = create_assistant_output_event("Hello world");
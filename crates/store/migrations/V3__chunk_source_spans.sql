@@ -0,0 +1,12 @@
+-- Record where each chunk came from in its source file
+-- V3__chunk_source_spans.sql
+
+-- Byte and character ranges into the original text plus 1-based line numbers.
+-- Nullable so rows ingested before this migration remain valid.
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS start_byte INTEGER,
+    ADD COLUMN IF NOT EXISTS end_byte INTEGER,
+    ADD COLUMN IF NOT EXISTS start_char INTEGER,
+    ADD COLUMN IF NOT EXISTS end_char INTEGER,
+    ADD COLUMN IF NOT EXISTS start_line INTEGER,
+    ADD COLUMN IF NOT EXISTS end_line INTEGER;
@@ -0,0 +1,23 @@
+-- Parent/child (small-to-big) retrieval
+-- V4__parent_sections.sql
+
+-- Larger source sections that small embedded chunks were cut from
+CREATE TABLE IF NOT EXISTS document_parents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    file_name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    heading_path TEXT[] NOT NULL DEFAULT '{}',
+    start_byte INTEGER,
+    end_byte INTEGER,
+    start_char INTEGER,
+    end_char INTEGER,
+    start_line INTEGER,
+    end_line INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS parent_id UUID REFERENCES document_parents(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_documents_parent_id ON documents(parent_id);
+CREATE INDEX IF NOT EXISTS idx_document_parents_file_name ON document_parents(file_name);
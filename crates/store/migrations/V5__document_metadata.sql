@@ -0,0 +1,9 @@
+-- Document metadata for filtered search
+-- V5__document_metadata.sql
+
+-- Source path, department, tags, language, mtime, checksum and custom keys
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS metadata JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+-- Default jsonb_ops supports the containment and key-existence operators used by filters
+CREATE INDEX IF NOT EXISTS idx_documents_metadata ON documents USING GIN (metadata);
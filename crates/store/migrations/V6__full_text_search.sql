@@ -0,0 +1,10 @@
+-- Full-text search for hybrid retrieval
+-- V6__full_text_search.sql
+
+-- Lexical index over chunk content so exact terms (policy IDs, product codes,
+-- acronyms) can be matched alongside vector similarity
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS content_tsv tsvector
+    GENERATED ALWAYS AS (to_tsvector('english', content)) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_documents_content_tsv ON documents USING GIN (content_tsv);
@@ -0,0 +1,6 @@
+-- Vector index managed by the application
+-- V7__managed_vector_index.sql
+
+-- The embedding index type, parameters and distance metric are configurable, so
+-- VectorStore creates and rebuilds it at startup instead of a fixed migration
+DROP INDEX IF EXISTS documents_embedding_idx;
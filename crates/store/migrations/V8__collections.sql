@@ -0,0 +1,32 @@
+-- Collections with their own embedding model and dimension
+-- V8__collections.sql
+
+-- Registry of collections; VectorStore creates entries (and their vector
+-- indexes) at runtime, so switching embedding models needs no schema change
+CREATE TABLE IF NOT EXISTS collections (
+    name TEXT PRIMARY KEY,
+    embedding_model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL CHECK (dimensions > 0),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Existing chunks become the 'default' collection at the column's old dimension
+INSERT INTO collections (name, embedding_model, dimensions)
+SELECT 'default', 'unknown', atttypmod
+FROM pg_attribute
+WHERE attrelid = 'documents'::regclass
+  AND attname = 'embedding'
+  AND atttypmod > 0
+  AND EXISTS (SELECT 1 FROM documents)
+ON CONFLICT (name) DO NOTHING;
+
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS collection TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE document_parents
+    ADD COLUMN IF NOT EXISTS collection TEXT NOT NULL DEFAULT 'default';
+
+-- Dimensionless column; each collection is indexed through a typed cast
+ALTER TABLE documents ALTER COLUMN embedding TYPE vector;
+
+CREATE INDEX IF NOT EXISTS idx_documents_collection ON documents(collection);
+CREATE INDEX IF NOT EXISTS idx_document_parents_collection ON document_parents(collection);
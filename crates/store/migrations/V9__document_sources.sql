@@ -0,0 +1,7 @@
+-- Document lifecycle lookups
+-- V9__document_sources.sql
+
+-- Chunks are identified by their source file: the metadata source path, or the
+-- file name for documents added without one
+CREATE INDEX IF NOT EXISTS idx_documents_source
+    ON documents (collection, (COALESCE(metadata->>'source_path', file_name)));
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Collection used when none is configured
+pub const DEFAULT_COLLECTION: &str = "default";
+
+/// Embedding model recorded for collections created without one; it matches any model
+pub const UNKNOWN_MODEL: &str = "unknown";
+
+/// A named set of chunks embedded with one model; each collection has its own
+/// vector dimension and index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collection {
+    pub name: String,
+    pub embedding_model: String,
+    pub dimensions: usize,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Collection {
+    pub fn new(
+        name: impl Into<String>,
+        embedding_model: impl Into<String>,
+        dimensions: usize,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            embedding_model: embedding_model.into(),
+            dimensions,
+            created_at: None,
+        }
+    }
+
+    /// Collection names are inlined into SQL (partial indexes need literal
+    /// predicates), so they are restricted to lowercase identifiers
+    pub fn validate(&self) -> Result<()> {
+        let valid = self.name.len() <= 48
+            && self.name.starts_with(|c: char| c.is_ascii_lowercase())
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            anyhow::bail!(
+                "Invalid collection name '{}': use up to 48 lowercase letters, digits or underscores, starting with a letter",
+                self.name
+            );
+        }
+        if self.dimensions == 0 {
+            anyhow::bail!("Collection '{}' must have a positive dimension", self.name);
+        }
+        Ok(())
+    }
+
+    /// Rejects reusing this stored collection for `requested` when the dimension
+    /// differs or both name a different embedding model
+    pub fn ensure_compatible(&self, requested: &Collection) -> Result<()> {
+        let model_changed = self.embedding_model != requested.embedding_model
+            && self.embedding_model != UNKNOWN_MODEL
+            && requested.embedding_model != UNKNOWN_MODEL;
+        if self.dimensions != requested.dimensions || model_changed {
+            anyhow::bail!(
+                "Embedding dimension mismatch: collection '{}' holds {} embedding dimensions from '{}' but the embedding provider produces {} dimensions from '{}'. \
+                 Configure a new collection to re-embed the documents with this provider.",
+                self.name,
+                self.dimensions,
+                self.embedding_model,
+                requested.dimensions,
+                requested.embedding_model
+            );
+        }
+        Ok(())
+    }
+
+    /// Rejects embeddings that do not have the collection's dimension
+    pub fn check_dimensions(&self, embedding: &[f32]) -> Result<()> {
+        if embedding.len() != self.dimensions {
+            anyhow::bail!(
+                "Embedding dimension mismatch: expected {}, got {}",
+                self.dimensions,
+                embedding.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Name of the collection's vector index
+    pub fn index_name(&self) -> String {
+        format!("documents_embedding_{}_idx", self.name)
+    }
+
+    /// The embedding column cast to this collection's dimension, matching its index
+    pub fn embedding_sql(&self) -> String {
+        format!("(embedding::vector({}))", self.dimensions)
+    }
+
+    /// Row predicate selecting this collection
+    pub fn predicate_sql(&self) -> String {
+        format!("collection = '{}'", self.name)
+    }
+}
+
+/// Size of a collection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionStats {
+    pub name: String,
+    pub embedding_model: String,
+    pub dimensions: usize,
+    /// Stored chunks
+    pub documents: i64,
+    /// Distinct source files
+    pub files: i64,
+    /// Stored parent sections
+    pub parents: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_validate_collection_names() {
+        assert!(Collection::new("hr_docs_2", "titan", 512)
+            .validate()
+            .is_ok());
+        assert!(Collection::new("HR", "titan", 512).validate().is_err());
+        assert!(
+            Collection::new("hr'; DROP TABLE documents; --", "titan", 512)
+                .validate()
+                .is_err()
+        );
+        assert!(Collection::new("2024", "titan", 512).validate().is_err());
+        assert!(Collection::new("hr", "titan", 0).validate().is_err());
+    }
+
+    #[test]
+    fn should_reject_reuse_with_other_dimension_or_model() {
+        let stored = Collection::new("hr", "titan", 512);
+
+        assert!(stored
+            .ensure_compatible(&Collection::new("hr", "titan", 512))
+            .is_ok());
+        assert!(stored
+            .ensure_compatible(&Collection::new("hr", UNKNOWN_MODEL, 512))
+            .is_ok());
+        assert!(stored
+            .ensure_compatible(&Collection::new("hr", "titan", 1024))
+            .is_err());
+        assert!(stored
+            .ensure_compatible(&Collection::new("hr", "cohere", 512))
+            .is_err());
+    }
+
+    #[test]
+    fn should_cast_embeddings_to_collection_dimension() {
+        let collection = Collection::new("engineering", "cohere", 1024);
+
+        assert_eq!(collection.embedding_sql(), "(embedding::vector(1024))");
+        assert_eq!(collection.predicate_sql(), "collection = 'engineering'");
+        assert_eq!(
+            collection.index_name(),
+            "documents_embedding_engineering_idx"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Behaviour every [`VectorStore`] backend must share. Backends run [`run`] from
+//! their tests; each check works in its own collection, which it drops again.
+
+use anyhow::{ensure, Result};
+use std::sync::Arc;
+
+use crate::collection::Collection;
+use crate::filter::MetadataFilter;
+use crate::models::{DocumentChunk, DocumentMetadata, ParentDocument, SearchOptions};
+use crate::vector_store::VectorStore;
+
+/// Runs every check against `store`, embedding chunks with `dimensions` values
+pub async fn run(store: &dyn VectorStore, dimensions: usize) -> Result<()> {
+    check_insert_and_read_back(store, dimensions).await?;
+    check_search_with_filter(store, dimensions).await?;
+    check_heading_path_round_trip(store, dimensions).await?;
+    check_search_ranking(store, dimensions).await?;
+    check_upsert_and_delete(store, dimensions).await?;
+    check_collection_stats(store, dimensions).await?;
+    check_collections_are_isolated(store, dimensions).await?;
+    Ok(())
+}
+
+/// A fresh collection named `conformance_<check>`, emptied of earlier runs
+async fn fresh_collection(
+    store: &dyn VectorStore,
+    check: &str,
+    dimensions: usize,
+) -> Result<Arc<dyn VectorStore>> {
+    let name = format!("conformance_{}", check);
+    // A failed earlier run may have left the collection behind
+    let _ = store.drop_collection(&name).await;
+    store
+        .collection_store(&Collection::new(name, "conformance", dimensions))
+        .await
+}
+
+fn source_path(path: &str) -> DocumentMetadata {
+    DocumentMetadata {
+        source_path: Some(path.to_string()),
+        ..DocumentMetadata::default()
+    }
+}
+
+fn chunk(file_name: &str, chunk_id: usize, dimensions: usize) -> DocumentChunk {
+    DocumentChunk::new(
+        file_name.to_string(),
+        chunk_id,
+        format!("{} chunk {}", file_name, chunk_id),
+        vec![1.0; dimensions],
+    )
+}
+
+pub async fn check_insert_and_read_back(store: &dyn VectorStore, dimensions: usize) -> Result<()> {
+    let collection = fresh_collection(store, "insert", dimensions).await?;
+
+    let single = collection
+        .insert_document(chunk("faq.md", 0, dimensions))
+        .await?;
+    ensure!(
+        single.content == "faq.md chunk 0",
+        "inserted content changed"
+    );
+    let batch = collection
+        .insert_documents_batch((1..4).map(|id| chunk("faq.md", id, dimensions)).collect())
+        .await?;
+    ensure!(
+        batch.len() == 3,
+        "batch insert returned {} documents",
+        batch.len()
+    );
+    ensure!(
+        collection.get_document_count().await? == 4,
+        "count after inserts"
+    );
+
+    let chunks = collection.get_chunks("faq.md").await?;
+    let ids: Vec<usize> = chunks.iter().map(|doc| doc.chunk_id).collect();
+    ensure!(ids == vec![0, 1, 2, 3], "chunks out of order: {:?}", ids);
+    ensure!(
+        chunks[1..] == batch[..],
+        "batch chunks not read back unchanged"
+    );
+
+    store.drop_collection(collection.collection_name()).await
+}
+
+pub async fn check_search_with_filter(store: &dyn VectorStore, dimensions: usize) -> Result<()> {
+    let collection = fresh_collection(store, "search", dimensions).await?;
+    for (file_name, department) in [("leave.md", "HR"), ("vpn.md", "IT")] {
+        let metadata = DocumentMetadata {
+            department: Some(department.to_string()),
+            ..Default::default()
+        };
+        collection
+            .insert_document(chunk(file_name, 0, dimensions).with_metadata(metadata))
+            .await?;
+    }
+    let query = vec![1.0; dimensions];
+
+    let all = collection
+        .search_similar(query.clone(), 5, &SearchOptions::default())
+        .await?;
+    ensure!(all.len() == 2, "unfiltered search returned {}", all.len());
+    let limited = collection
+        .search_similar(query.clone(), 1, &SearchOptions::default())
+        .await?;
+    ensure!(limited.len() == 1, "limit not applied");
+    let filtered = collection
+        .search_similar(
+            query,
+            5,
+            &SearchOptions {
+                filter: Some(MetadataFilter::parse("department = 'HR'")?),
+                ..SearchOptions::default()
+            },
+        )
+        .await?;
+    ensure!(
+        filtered.len() == 1 && filtered[0].document.file_name == "leave.md",
+        "filter not applied"
+    );
+
+    store.drop_collection(collection.collection_name()).await
+}
+
+pub async fn check_heading_path_round_trip(
+    store: &dyn VectorStore,
+    dimensions: usize,
+) -> Result<()> {
+    let collection = fresh_collection(store, "heading_path", dimensions).await?;
+    for (chunk_id, heading_path) in [(0, vec!["Setup", "Linux"]), (1, vec!["Usage"])] {
+        let metadata = DocumentMetadata {
+            heading_path: heading_path.into_iter().map(String::from).collect(),
+            ..source_path("docs/guide.md")
+        };
+        collection
+            .insert_document(chunk("guide.md", chunk_id, dimensions).with_metadata(metadata))
+            .await?;
+    }
+
+    let chunks = collection.get_chunks("docs/guide.md").await?;
+    let paths: Vec<Vec<String>> = chunks
+        .iter()
+        .map(|doc| doc.metadata.heading_path.clone())
+        .collect();
+    ensure!(
+        paths == vec![vec!["Setup", "Linux"], vec!["Usage"]],
+        "heading paths not read back: {:?}",
+        paths
+    );
+    let filtered = collection
+        .search_similar(
+            vec![1.0; dimensions],
+            5,
+            &SearchOptions {
+                filter: Some(MetadataFilter::parse("heading_path ? 'Linux'")?),
+                ..SearchOptions::default()
+            },
+        )
+        .await?;
+    ensure!(
+        filtered.len() == 1 && filtered[0].document.chunk_id == 0,
+        "heading path filter not applied"
+    );
+
+    store.drop_collection(collection.collection_name()).await
+}
+
+/// Embedding along the `axes` basis vectors, e.g. `[1, 1, 0, ..]` for `&[0, 1]`
+fn embedding(axes: &[usize], dimensions: usize) -> Vec<f32> {
+    let mut embedding = vec![0.0; dimensions];
+    for &axis in axes {
+        embedding[axis] = 1.0;
+    }
+    embedding
+}
+
+/// Needs at least two dimensions
+pub async fn check_search_ranking(store: &dyn VectorStore, dimensions: usize) -> Result<()> {
+    let collection = fresh_collection(store, "ranking", dimensions).await?;
+    for (file_name, axes) in [
+        ("orthogonal.md", &[1][..]),
+        ("partial.md", &[0, 1][..]),
+        ("exact.md", &[0][..]),
+    ] {
+        let mut chunk = chunk(file_name, 0, dimensions);
+        chunk.embedding = embedding(axes, dimensions);
+        collection.insert_document(chunk).await?;
+    }
+    let query = embedding(&[0], dimensions);
+
+    let results = collection
+        .search_similar(query.clone(), 5, &SearchOptions::default())
+        .await?;
+    let files: Vec<&str> = results
+        .iter()
+        .map(|result| result.document.file_name.as_str())
+        .collect();
+    ensure!(
+        files == vec!["exact.md", "partial.md"],
+        "ranked {:?}, expected the orthogonal chunk below the threshold",
+        files
+    );
+    ensure!(
+        (results[0].similarity - 1.0).abs() < 1e-4
+            && (results[1].similarity - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-4,
+        "similarities {:?} are not cosine",
+        results.iter().map(|r| r.similarity).collect::<Vec<_>>()
+    );
+
+    collection.delete_by_file("exact.md").await?;
+    let results = collection
+        .search_similar(query, 5, &SearchOptions::default())
+        .await?;
+    ensure!(
+        results.len() == 1 && results[0].document.file_name == "partial.md",
+        "deleted chunk still found"
+    );
+
+    store.drop_collection(collection.collection_name()).await
+}
+
+pub async fn check_upsert_and_delete(store: &dyn VectorStore, dimensions: usize) -> Result<()> {
+    let collection = fresh_collection(store, "delete", dimensions).await?;
+    let parent = ParentDocument::new("policy.md".to_string(), "v1".to_string(), vec![]);
+    let v1: Vec<DocumentChunk> = (0..3)
+        .map(|id| chunk("policy.md", id, dimensions).with_parent(parent.id))
+        .collect();
+    collection
+        .upsert_document("policy.md", &[parent], v1)
+        .await?;
+    let v2 = (0..2)
+        .map(|id| chunk("policy.md", id, dimensions))
+        .collect();
+    collection.upsert_document("policy.md", &[], v2).await?;
+    collection
+        .insert_document(chunk("notes.txt", 0, dimensions))
+        .await?;
+
+    let info = collection.get_document("policy.md").await?;
+    ensure!(
+        info.is_some_and(|info| info.chunks == 2),
+        "upsert kept old chunks"
+    );
+    ensure!(
+        collection.collection_stats().await?.parents == 0,
+        "upsert kept old parent sections"
+    );
+    ensure!(
+        collection
+            .upsert_document("other.md", &[], vec![chunk("policy.md", 0, dimensions)])
+            .await
+            .is_err(),
+        "upsert accepted chunks of another source"
+    );
+
+    ensure!(
+        collection.delete_by_file("policy.md").await? == 2,
+        "deleted count"
+    );
+    ensure!(
+        collection.get_document("policy.md").await?.is_none(),
+        "deleted file still listed"
+    );
+    let sources: Vec<String> = collection
+        .list_documents()
+        .await?
+        .into_iter()
+        .map(|info| info.source)
+        .collect();
+    ensure!(
+        sources == vec!["notes.txt"],
+        "remaining sources: {:?}",
+        sources
+    );
+
+    collection.delete_all_documents().await?;
+    ensure!(collection.get_document_count().await? == 0, "delete all");
+
+    store.drop_collection(collection.collection_name()).await
+}
+
+pub async fn check_collection_stats(store: &dyn VectorStore, dimensions: usize) -> Result<()> {
+    let collection = fresh_collection(store, "stats", dimensions).await?;
+    let parent = ParentDocument::new("a.md".to_string(), "Section".to_string(), vec![]);
+    collection.insert_parent(&parent).await?;
+    collection
+        .insert_documents_batch(vec![
+            chunk("a.md", 0, dimensions).with_parent(parent.id),
+            chunk("a.md", 1, dimensions).with_parent(parent.id),
+            chunk("b.md", 0, dimensions),
+            // Same file name in two directories: two source files
+            chunk("README.md", 0, dimensions).with_metadata(source_path("docs/README.md")),
+            chunk("README.md", 0, dimensions).with_metadata(source_path("api/README.md")),
+        ])
+        .await?;
+
+    let stats = collection.collection_stats().await?;
+    ensure!(
+        stats.name == "conformance_stats",
+        "stats name {}",
+        stats.name
+    );
+    ensure!(
+        (stats.documents, stats.files, stats.parents) == (5, 4, 1),
+        "stats counts {:?}",
+        (stats.documents, stats.files, stats.parents)
+    );
+
+    store.drop_collection(collection.collection_name()).await
+}
+
+pub async fn check_collections_are_isolated(
+    store: &dyn VectorStore,
+    dimensions: usize,
+) -> Result<()> {
+    let first = fresh_collection(store, "first", dimensions).await?;
+    let second = fresh_collection(store, "second", dimensions).await?;
+    first.insert_document(chunk("a.md", 0, dimensions)).await?;
+
+    let hits = second
+        .search_similar(vec![1.0; dimensions], 5, &SearchOptions::default())
+        .await?;
+    ensure!(hits.is_empty(), "search crossed collections");
+    ensure!(
+        second.get_document_count().await? == 0,
+        "count crossed collections"
+    );
+
+    let names: Vec<String> = store
+        .list_collections()
+        .await?
+        .into_iter()
+        .map(|collection| collection.name)
+        .collect();
+    ensure!(
+        names.iter().any(|name| name == "conformance_first"),
+        "created collection not listed"
+    );
+
+    store.drop_collection("conformance_first").await?;
+    store.drop_collection("conformance_second").await?;
+    ensure!(
+        store.drop_collection("conformance_first").await.is_err(),
+        "dropping a missing collection succeeded"
+    );
+    Ok(())
+}
@@ -0,0 +1,531 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Comparison operators supported in metadata filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn sql(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "<>",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+}
+
+/// Boolean filter over document metadata, e.g.
+/// `department = 'HR' AND tags ? 'policy' AND NOT language IN ('rust', 'python')`.
+///
+/// Fields are metadata keys, with dots for nested objects (`owner.team`). Values are
+/// single-quoted strings, numbers or `true`/`false`. `field ? 'x'` holds when the
+/// field is an array containing `'x'`, the string `'x'`, or an object with key `'x'`.
+/// Comparisons against missing fields or values of another type are false.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataFilter {
+    And(Box<MetadataFilter>, Box<MetadataFilter>),
+    Or(Box<MetadataFilter>, Box<MetadataFilter>),
+    Not(Box<MetadataFilter>),
+    Compare {
+        field: Vec<String>,
+        op: CompareOp,
+        value: Value,
+    },
+    Contains {
+        field: Vec<String>,
+        value: String,
+    },
+    In {
+        field: Vec<String>,
+        values: Vec<Value>,
+    },
+}
+
+/// Value bound to a placeholder of the SQL generated for a filter
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FilterParam {
+    Path(Vec<String>),
+    Json(Value),
+    Text(String),
+}
+
+impl FromStr for MetadataFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        Self::parse(input)
+    }
+}
+
+impl MetadataFilter {
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let filter = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            bail!("Invalid metadata filter: unexpected {:?}", token);
+        }
+        Ok(filter)
+    }
+
+    /// Evaluates the filter against a metadata object
+    pub fn matches(&self, metadata: &Value) -> bool {
+        match self {
+            MetadataFilter::And(left, right) => left.matches(metadata) && right.matches(metadata),
+            MetadataFilter::Or(left, right) => left.matches(metadata) || right.matches(metadata),
+            MetadataFilter::Not(inner) => !inner.matches(metadata),
+            MetadataFilter::Compare { field, op, value } => {
+                let actual = lookup(metadata, field);
+                match op {
+                    CompareOp::Eq => actual.is_some_and(|actual| json_eq(actual, value)),
+                    CompareOp::Ne => !actual.is_some_and(|actual| json_eq(actual, value)),
+                    _ => actual
+                        .and_then(|actual| json_cmp(actual, value))
+                        .is_some_and(|ordering| match op {
+                            CompareOp::Lt => ordering == Ordering::Less,
+                            CompareOp::Le => ordering != Ordering::Greater,
+                            CompareOp::Gt => ordering == Ordering::Greater,
+                            _ => ordering != Ordering::Less,
+                        }),
+                }
+            }
+            MetadataFilter::Contains { field, value } => match lookup(metadata, field) {
+                Some(Value::Array(items)) => items.iter().any(|item| item.as_str() == Some(value)),
+                Some(Value::String(actual)) => actual == value,
+                Some(Value::Object(object)) => object.contains_key(value),
+                _ => false,
+            },
+            MetadataFilter::In { field, values } => lookup(metadata, field)
+                .is_some_and(|actual| values.iter().any(|value| json_eq(actual, value))),
+        }
+    }
+
+    /// Renders the filter as a SQL condition on the JSONB `column`, numbering
+    /// placeholders after `first_param`. Every leaf is two-valued (never NULL) so
+    /// that `NOT` behaves like [`MetadataFilter::matches`].
+    pub(crate) fn to_sql(
+        &self,
+        column: &str,
+        first_param: usize,
+        params: &mut Vec<FilterParam>,
+    ) -> String {
+        let bind = |params: &mut Vec<FilterParam>, param: FilterParam| {
+            params.push(param);
+            format!("${}", first_param + params.len())
+        };
+
+        match self {
+            MetadataFilter::And(left, right) => format!(
+                "({} AND {})",
+                left.to_sql(column, first_param, params),
+                right.to_sql(column, first_param, params)
+            ),
+            MetadataFilter::Or(left, right) => format!(
+                "({} OR {})",
+                left.to_sql(column, first_param, params),
+                right.to_sql(column, first_param, params)
+            ),
+            MetadataFilter::Not(inner) => {
+                format!("(NOT {})", inner.to_sql(column, first_param, params))
+            }
+            MetadataFilter::Compare { field, op, value } => {
+                let path = bind(params, FilterParam::Path(field.clone()));
+                let value = bind(params, FilterParam::Json(value.clone()));
+                match op {
+                    CompareOp::Eq => format!("COALESCE({column} #> {path} = {value}, false)"),
+                    CompareOp::Ne => format!("({column} #> {path} IS DISTINCT FROM {value})"),
+                    // jsonb orders values of different types, so require matching types
+                    _ => format!(
+                        "COALESCE(jsonb_typeof({column} #> {path}) = jsonb_typeof({value}) AND {column} #> {path} {} {value}, false)",
+                        op.sql()
+                    ),
+                }
+            }
+            MetadataFilter::Contains { field, value } => {
+                let path = bind(params, FilterParam::Path(field.clone()));
+                let value = bind(params, FilterParam::Text(value.clone()));
+                format!("COALESCE({column} #> {path} ? {value}, false)")
+            }
+            MetadataFilter::In { field, values } => {
+                let path = bind(params, FilterParam::Path(field.clone()));
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| bind(params, FilterParam::Json(value.clone())))
+                    .collect();
+                format!(
+                    "COALESCE({column} #> {path} IN ({}), false)",
+                    values.join(", ")
+                )
+            }
+        }
+    }
+}
+
+fn lookup<'a>(metadata: &'a Value, field: &[String]) -> Option<&'a Value> {
+    field
+        .iter()
+        .try_fold(metadata, |value, key| value.as_object()?.get(key))
+        .filter(|value| !value.is_null())
+}
+
+fn json_eq(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => actual == expected,
+    }
+}
+
+fn json_cmp(actual: &Value, expected: &Value) -> Option<Ordering> {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ',' | '?' | '=' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    ',' => Token::Comma,
+                    '?' => Token::Op("?"),
+                    _ => Token::Op("="),
+                });
+            }
+            '!' | '<' | '>' => {
+                chars.next();
+                let next = chars.peek().map(|&(_, next)| next);
+                let op = match (c, next) {
+                    ('!', Some('=')) | ('<', Some('>')) => "!=",
+                    ('<', Some('=')) => "<=",
+                    ('>', Some('=')) => ">=",
+                    ('<', _) => "<",
+                    ('>', _) => ">",
+                    _ => bail!("Invalid metadata filter: unexpected '!' at {}", start),
+                };
+                if op.len() == 2 {
+                    chars.next();
+                }
+                tokens.push(Token::Op(op));
+            }
+            '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // '' escapes a quote, as in SQL
+                        Some((_, '\'')) if chars.peek().map(|&(_, next)| next) == Some('\'') => {
+                            chars.next();
+                            value.push('\'');
+                        }
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => value.push(c),
+                        None => bail!("Invalid metadata filter: unterminated string"),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.' || (c == '-' && i == start)) {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let number = &input[start..end];
+                tokens.push(Token::Num(number.parse().map_err(|_| {
+                    anyhow!("Invalid metadata filter: bad number '{}'", number)
+                })?));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Ident(input[start..end].to_string()));
+            }
+            _ => bail!(
+                "Invalid metadata filter: unexpected character '{}' at {}",
+                c,
+                start
+            ),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => bail!(
+                "Invalid metadata filter: expected {:?}, found {:?}",
+                expected,
+                other
+            ),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<MetadataFilter> {
+        let mut filter = self.parse_and()?;
+        while self.eat_keyword("OR") {
+            filter = MetadataFilter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<MetadataFilter> {
+        let mut filter = self.parse_unary()?;
+        while self.eat_keyword("AND") {
+            filter = MetadataFilter::And(Box::new(filter), Box::new(self.parse_unary()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_unary(&mut self) -> Result<MetadataFilter> {
+        if self.eat_keyword("NOT") {
+            return Ok(MetadataFilter::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.position += 1;
+            let filter = self.parse_or()?;
+            self.expect(Token::RParen)?;
+            return Ok(filter);
+        }
+        self.parse_condition()
+    }
+
+    fn parse_condition(&mut self) -> Result<MetadataFilter> {
+        let field = match self.next() {
+            Some(Token::Ident(ident)) if !is_keyword(&ident) => ident
+                .split('.')
+                .map(|part| {
+                    if part.is_empty() {
+                        bail!("Invalid metadata filter: bad field name '{}'", ident)
+                    }
+                    Ok(part.to_string())
+                })
+                .collect::<Result<Vec<_>>>()?,
+            other => bail!("Invalid metadata filter: expected field, found {:?}", other),
+        };
+
+        if self.eat_keyword("IN") {
+            self.expect(Token::LParen)?;
+            let mut values = vec![self.parse_value()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+                values.push(self.parse_value()?);
+            }
+            self.expect(Token::RParen)?;
+            return Ok(MetadataFilter::In { field, values });
+        }
+
+        let op = match self.next() {
+            Some(Token::Op("?")) => {
+                return match self.parse_value()? {
+                    Value::String(value) => Ok(MetadataFilter::Contains { field, value }),
+                    other => bail!(
+                        "Invalid metadata filter: '?' expects a string, found {}",
+                        other
+                    ),
+                };
+            }
+            Some(Token::Op("=")) => CompareOp::Eq,
+            Some(Token::Op("!=")) => CompareOp::Ne,
+            Some(Token::Op("<")) => CompareOp::Lt,
+            Some(Token::Op("<=")) => CompareOp::Le,
+            Some(Token::Op(">")) => CompareOp::Gt,
+            Some(Token::Op(">=")) => CompareOp::Ge,
+            other => bail!(
+                "Invalid metadata filter: expected operator, found {:?}",
+                other
+            ),
+        };
+        let value = self.parse_value()?;
+        Ok(MetadataFilter::Compare { field, op, value })
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        match self.next() {
+            Some(Token::Str(value)) => Ok(Value::String(value)),
+            Some(Token::Num(value)) => serde_json::Number::from_f64(value)
+                .map(Value::Number)
+                .ok_or_else(|| anyhow!("Invalid metadata filter: bad number {}", value)),
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("true") => {
+                Ok(Value::Bool(true))
+            }
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("false") => {
+                Ok(Value::Bool(false))
+            }
+            other => bail!("Invalid metadata filter: expected value, found {:?}", other),
+        }
+    }
+}
+
+fn is_keyword(ident: &str) -> bool {
+    ["AND", "OR", "NOT", "IN"]
+        .iter()
+        .any(|keyword| ident.eq_ignore_ascii_case(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn metadata() -> Value {
+        json!({
+            "department": "HR",
+            "tags": ["policy", "benefits"],
+            "language": "markdown",
+            "version": 3,
+            "owner": {"team": "people-ops"}
+        })
+    }
+
+    fn matches(filter: &str) -> bool {
+        MetadataFilter::parse(filter).unwrap().matches(&metadata())
+    }
+
+    #[test]
+    fn should_parse_and_match_equality_with_tag_membership() {
+        assert!(matches("department = 'HR' AND tags ? 'policy'"));
+        assert!(!matches("department = 'HR' AND tags ? 'security'"));
+    }
+
+    #[test]
+    fn should_respect_precedence_and_parentheses() {
+        assert!(matches(
+            "department = 'IT' OR department = 'HR' AND version = 3"
+        ));
+        assert!(!matches(
+            "(department = 'IT' OR department = 'HR') AND version = 4"
+        ));
+        assert!(matches("NOT (department = 'IT')"));
+    }
+
+    #[test]
+    fn should_compare_numbers_strings_and_nested_fields() {
+        assert!(matches("version >= 3 AND version < 4"));
+        assert!(matches("owner.team = 'people-ops'"));
+        assert!(matches("language IN ('rust', 'markdown')"));
+        assert!(matches("department != 'IT'"));
+        // Mismatched types and missing fields never satisfy a comparison
+        assert!(!matches("department > 3"));
+        assert!(!matches("missing = 'x'"));
+        assert!(matches("NOT missing = 'x'"));
+    }
+
+    #[test]
+    fn should_unescape_doubled_quotes() {
+        let filter = MetadataFilter::parse("title = 'Employee''s guide'").unwrap();
+
+        assert!(filter.matches(&json!({"title": "Employee's guide"})));
+    }
+
+    #[test]
+    fn should_reject_malformed_filters() {
+        for input in [
+            "department =",
+            "department = 'HR' AND",
+            "= 'HR'",
+            "tags ? 3",
+            "department = 'HR",
+            "(department = 'HR'",
+        ] {
+            assert!(MetadataFilter::parse(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn should_render_parameterised_sql() {
+        let filter = MetadataFilter::parse("department = 'HR' AND tags ? 'policy'").unwrap();
+        let mut params = Vec::new();
+
+        let sql = filter.to_sql("metadata", 2, &mut params);
+
+        assert_eq!(
+            sql,
+            "(COALESCE(metadata #> $3 = $4, false) AND COALESCE(metadata #> $5 ? $6, false))"
+        );
+        assert_eq!(
+            params,
+            vec![
+                FilterParam::Path(vec!["department".to_string()]),
+                FilterParam::Json(json!("HR")),
+                FilterParam::Path(vec!["tags".to_string()]),
+                FilterParam::Text("policy".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::SearchResult;
+
+/// Combines lexical full-text ranking with vector ranking via reciprocal rank fusion
+#[derive(Debug, Clone, PartialEq)]
+pub struct HybridSearch {
+    /// Query text matched against the full-text index
+    pub query_text: String,
+    pub vector_weight: f32,
+    pub lexical_weight: f32,
+    /// RRF constant; larger values flatten the advantage of top ranks
+    pub rrf_k: f32,
+}
+
+impl HybridSearch {
+    pub fn new(query_text: impl Into<String>) -> Self {
+        Self {
+            query_text: query_text.into(),
+            vector_weight: 1.0,
+            lexical_weight: 1.0,
+            rrf_k: 60.0,
+        }
+    }
+
+    /// Fuses the vector and lexical result lists, best first
+    pub fn fuse(&self, vector: Vec<SearchResult>, lexical: Vec<SearchResult>) -> Vec<SearchResult> {
+        reciprocal_rank_fusion(
+            vec![(self.vector_weight, vector), (self.lexical_weight, lexical)],
+            self.rrf_k,
+        )
+    }
+}
+
+/// Reciprocal rank fusion: each document scores `sum(weight / (k + rank))` over the
+/// ranked lists it appears in (ranks start at 1). Ties keep the higher similarity.
+pub fn reciprocal_rank_fusion(
+    ranked_lists: Vec<(f32, Vec<SearchResult>)>,
+    k: f32,
+) -> Vec<SearchResult> {
+    let mut fused: HashMap<Uuid, (f32, SearchResult)> = HashMap::new();
+
+    for (weight, results) in ranked_lists {
+        for (index, result) in results.into_iter().enumerate() {
+            let score = weight / (k + index as f32 + 1.0);
+            fused
+                .entry(result.document.id)
+                .and_modify(|(total, _)| *total += score)
+                .or_insert((score, result));
+        }
+    }
+
+    let mut fused: Vec<(f32, SearchResult)> = fused.into_values().collect();
+    fused.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .total_cmp(a_score)
+            .then(b.similarity.total_cmp(&a.similarity))
+    });
+    fused.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Lowercased alphanumeric terms, as indexed by the lexical rankers
+pub fn lexical_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Okapi BM25 score of every document for `query` (k1 = 1.2, b = 0.75);
+/// documents sharing no term with the query score 0
+pub fn bm25_scores(query: &str, documents: &[&str]) -> Vec<f32> {
+    const K1: f32 = 1.2;
+    const B: f32 = 0.75;
+
+    let documents: Vec<Vec<String>> = documents.iter().map(|doc| lexical_terms(doc)).collect();
+    let mut query_terms = lexical_terms(query);
+    query_terms.sort();
+    query_terms.dedup();
+
+    let count = documents.len() as f32;
+    let average_length = documents.iter().map(Vec::len).sum::<usize>() as f32 / count.max(1.0);
+
+    let idf: Vec<f32> = query_terms
+        .iter()
+        .map(|term| {
+            let containing = documents.iter().filter(|doc| doc.contains(term)).count() as f32;
+            ((count - containing + 0.5) / (containing + 0.5) + 1.0).ln()
+        })
+        .collect();
+
+    documents
+        .iter()
+        .map(|doc| {
+            let length_norm = 1.0 - B + B * doc.len() as f32 / average_length.max(1.0);
+            query_terms
+                .iter()
+                .zip(&idf)
+                .map(|(term, idf)| {
+                    let frequency = doc.iter().filter(|t| *t == term).count() as f32;
+                    idf * frequency * (K1 + 1.0) / (frequency + K1 * length_norm)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Document;
+
+    fn result(content: &str, similarity: f32) -> SearchResult {
+        SearchResult::new(
+            Document::new("doc.txt".to_string(), 0, content.to_string(), vec![]),
+            similarity,
+        )
+    }
+
+    #[test]
+    fn should_rank_documents_found_by_both_lists_first() {
+        let (a, b, c) = (result("a", 0.9), result("b", 0.8), result("c", 0.1));
+
+        let fused =
+            HybridSearch::new("query").fuse(vec![a.clone(), b.clone()], vec![c.clone(), b.clone()]);
+
+        let contents: Vec<&str> = fused.iter().map(|r| r.document.content.as_str()).collect();
+        assert_eq!(contents, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn should_apply_list_weights() {
+        let (a, c) = (result("a", 0.9), result("c", 0.1));
+        let search = HybridSearch {
+            lexical_weight: 2.0,
+            ..HybridSearch::new("query")
+        };
+
+        let fused = search.fuse(vec![a], vec![c]);
+
+        assert_eq!(fused[0].document.content, "c");
+    }
+
+    #[test]
+    fn should_score_exact_terms_with_bm25() {
+        let documents = [
+            "Policy HR-2024-07 covers parental leave.",
+            "General leave guidance for all staff and leave planning.",
+            "VPN setup instructions.",
+        ];
+
+        let scores = bm25_scores("What does HR-2024-07 say?", &documents);
+
+        assert!(scores[0] > scores[1]);
+        assert_eq!(scores[2], 0.0);
+    }
+}
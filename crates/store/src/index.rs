@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+
+use crate::collection::Collection;
+
+/// Distance used to compare embeddings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    #[default]
+    Cosine,
+    L2,
+    InnerProduct,
+}
+
+impl DistanceMetric {
+    /// pgvector distance operator; smaller is closer for every metric
+    pub fn operator(self) -> &'static str {
+        match self {
+            DistanceMetric::Cosine => "<=>",
+            DistanceMetric::L2 => "<->",
+            DistanceMetric::InnerProduct => "<#>",
+        }
+    }
+
+    /// Operator class the index must be built with for `operator` to use it
+    pub fn operator_class(self) -> &'static str {
+        match self {
+            DistanceMetric::Cosine => "vector_cosine_ops",
+            DistanceMetric::L2 => "vector_l2_ops",
+            DistanceMetric::InnerProduct => "vector_ip_ops",
+        }
+    }
+
+    /// SQL expression turning the distance between `column` and `param` into a
+    /// similarity where larger is closer
+    pub fn similarity_sql(self, column: &str, param: &str) -> String {
+        let distance = format!("({} {} {})", column, self.operator(), param);
+        match self {
+            DistanceMetric::Cosine => format!("1 - {}", distance),
+            DistanceMetric::L2 => format!("1 / (1 + {})", distance),
+            // `<#>` returns the negative inner product
+            DistanceMetric::InnerProduct => format!("-{}", distance),
+        }
+    }
+}
+
+/// Largest `hnsw.ef_search` pgvector accepts
+const MAX_EF_SEARCH: usize = 1000;
+
+/// Approximate nearest neighbour index algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexType {
+    #[default]
+    Hnsw,
+    IvfFlat,
+}
+
+/// Vector index settings; build parameters apply when the index is (re)built,
+/// search parameters (`ef_search`, `probes`) to every connection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VectorIndexConfig {
+    #[serde(rename = "type")]
+    pub index_type: IndexType,
+    pub metric: DistanceMetric,
+    /// HNSW: max connections per layer
+    pub m: u32,
+    /// HNSW: candidate list size while building
+    pub ef_construction: u32,
+    /// HNSW: candidate list size while searching
+    pub ef_search: u32,
+    /// IVFFlat: number of inverted lists, roughly rows / 1000
+    pub lists: u32,
+    /// IVFFlat: lists scanned per query
+    pub probes: u32,
+}
+
+impl Default for VectorIndexConfig {
+    fn default() -> Self {
+        Self {
+            index_type: IndexType::Hnsw,
+            metric: DistanceMetric::Cosine,
+            m: 16,
+            ef_construction: 64,
+            ef_search: 40,
+            lists: 100,
+            probes: 10,
+        }
+    }
+}
+
+impl VectorIndexConfig {
+    pub fn access_method(&self) -> &'static str {
+        match self.index_type {
+            IndexType::Hnsw => "hnsw",
+            IndexType::IvfFlat => "ivfflat",
+        }
+    }
+
+    /// Storage parameters as Postgres reports them in `pg_class.reloptions`
+    pub fn storage_options(&self) -> Vec<String> {
+        match self.index_type {
+            IndexType::Hnsw => vec![
+                format!("m={}", self.m),
+                format!("ef_construction={}", self.ef_construction),
+            ],
+            IndexType::IvfFlat => vec![format!("lists={}", self.lists)],
+        }
+    }
+
+    /// Partial index over the collection's rows, cast to its dimension
+    pub fn create_index_sql(&self, collection: &Collection) -> String {
+        format!(
+            "CREATE INDEX {} ON documents USING {} ({} {}) WITH ({}) WHERE {}",
+            collection.index_name(),
+            self.access_method(),
+            collection.embedding_sql(),
+            self.metric.operator_class(),
+            self.storage_options()
+                .iter()
+                .map(|option| option.replace('=', " = "))
+                .collect::<Vec<_>>()
+                .join(", "),
+            collection.predicate_sql()
+        )
+    }
+
+    /// Transaction-local setting that lets a search return `limit` rows: HNSW yields
+    /// at most `ef_search` candidates (capped at pgvector's maximum of 1000), so it is
+    /// raised when `limit` exceeds it. `None` when the session setting suffices.
+    pub fn search_limit_sql(&self, limit: usize) -> Option<String> {
+        match self.index_type {
+            IndexType::Hnsw if limit > self.ef_search as usize => Some(format!(
+                "SET LOCAL hnsw.ef_search = {}",
+                limit.min(MAX_EF_SEARCH)
+            )),
+            _ => None,
+        }
+    }
+
+    /// Session settings applied to each pooled connection
+    pub fn search_settings_sql(&self) -> String {
+        match self.index_type {
+            IndexType::Hnsw => format!("SET hnsw.ef_search = {}", self.ef_search),
+            IndexType::IvfFlat => format!("SET ivfflat.probes = {}", self.probes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_index_sql_for_each_type() {
+        let collection = Collection::new("hr", "titan", 512);
+        let hnsw = VectorIndexConfig::default();
+        let ivfflat = VectorIndexConfig {
+            index_type: IndexType::IvfFlat,
+            metric: DistanceMetric::L2,
+            lists: 200,
+            ..VectorIndexConfig::default()
+        };
+
+        assert_eq!(
+            hnsw.create_index_sql(&collection),
+            "CREATE INDEX documents_embedding_hr_idx ON documents USING hnsw ((embedding::vector(512)) vector_cosine_ops) WITH (m = 16, ef_construction = 64) WHERE collection = 'hr'"
+        );
+        assert_eq!(
+            ivfflat.create_index_sql(&collection),
+            "CREATE INDEX documents_embedding_hr_idx ON documents USING ivfflat ((embedding::vector(512)) vector_l2_ops) WITH (lists = 200) WHERE collection = 'hr'"
+        );
+        assert_eq!(ivfflat.search_settings_sql(), "SET ivfflat.probes = 10");
+    }
+
+    #[test]
+    fn should_raise_ef_search_for_larger_fetches() {
+        let hnsw = VectorIndexConfig::default();
+        let ivfflat = VectorIndexConfig {
+            index_type: IndexType::IvfFlat,
+            ..VectorIndexConfig::default()
+        };
+
+        assert_eq!(hnsw.search_limit_sql(40), None);
+        assert_eq!(
+            hnsw.search_limit_sql(60).as_deref(),
+            Some("SET LOCAL hnsw.ef_search = 60")
+        );
+        assert_eq!(
+            hnsw.search_limit_sql(5000).as_deref(),
+            Some("SET LOCAL hnsw.ef_search = 1000")
+        );
+        assert_eq!(ivfflat.search_limit_sql(500), None);
+    }
+
+    #[test]
+    fn should_express_similarity_for_each_metric() {
+        assert_eq!(
+            DistanceMetric::Cosine.similarity_sql("embedding", "$1"),
+            "1 - (embedding <=> $1)"
+        );
+        assert_eq!(
+            DistanceMetric::L2.similarity_sql("embedding", "$1"),
+            "1 / (1 + (embedding <-> $1))"
+        );
+        assert_eq!(
+            DistanceMetric::InnerProduct.similarity_sql("embedding", "$1"),
+            "-(embedding <#> $1)"
+        );
+    }
+
+    #[test]
+    fn should_deserialize_index_config() {
+        let config: VectorIndexConfig =
+            toml::from_str("type = \"ivfflat\"\nmetric = \"inner_product\"\nlists = 50\n").unwrap();
+
+        assert_eq!(config.index_type, IndexType::IvfFlat);
+        assert_eq!(config.metric, DistanceMetric::InnerProduct);
+        assert_eq!(config.lists, 50);
+        assert_eq!(config.m, 16);
+    }
+}
@@ -1,9 +1,27 @@
+pub mod collection;
+pub mod conformance;
+pub mod filter;
+pub mod hybrid;
+pub mod index;
+pub mod memory;
 pub mod migrations;
 pub mod models;
+pub mod postgres;
 pub mod session_store;
-pub mod store;
+pub mod sqlite;
+pub mod vector_store;
 
+pub use collection::{Collection, CollectionStats, DEFAULT_COLLECTION, UNKNOWN_MODEL};
+pub use filter::MetadataFilter;
+pub use hybrid::HybridSearch;
+pub use index::{DistanceMetric, IndexType, VectorIndexConfig};
+pub use memory::InMemoryVectorStore;
 pub use migrations::run_migrations;
-pub use models::{Document, DocumentChunk, Message, Role, SearchResult, SessionData};
+pub use models::{
+    dedupe_by_parent, Document, DocumentChunk, DocumentInfo, DocumentMetadata, Message,
+    ParentDocument, Role, SearchOptions, SearchResult, SessionData, SourceSpan,
+};
+pub use postgres::PgVectorStore;
 pub use session_store::RedisSessionStore;
-pub use store::VectorStore;
+pub use sqlite::SqliteVectorStore;
+pub use vector_store::VectorStore;
@@ -0,0 +1,718 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::collection::{Collection, CollectionStats, DEFAULT_COLLECTION, UNKNOWN_MODEL};
+use crate::hybrid::bm25_scores;
+use crate::models::{
+    dedupe_by_parent, Document, DocumentChunk, DocumentInfo, ParentDocument, SearchOptions,
+    SearchResult,
+};
+use crate::vector_store::{VectorStore, MIN_SIMILARITY};
+
+/// Vector store kept in process memory, for tests and local development; search
+/// scores every chunk by cosine similarity
+pub struct InMemoryVectorStore {
+    /// Collection this store reads and writes
+    collection: String,
+    collections: Arc<Mutex<HashMap<String, InMemoryCollection>>>,
+}
+
+/// Chunks and parent sections of one in-memory collection
+struct InMemoryCollection {
+    info: Collection,
+    documents: HashMap<Uuid, Document>,
+    /// Each chunk's embedding scaled to unit length, so search is one dot product
+    unit_embeddings: HashMap<Uuid, Vec<f32>>,
+    parents: HashMap<Uuid, ParentDocument>,
+}
+
+impl InMemoryCollection {
+    fn new(info: Collection) -> Self {
+        Self {
+            info,
+            documents: HashMap::new(),
+            unit_embeddings: HashMap::new(),
+            parents: HashMap::new(),
+        }
+    }
+
+    fn insert_document(&mut self, document: &Document) {
+        self.unit_embeddings
+            .insert(document.id, normalized(&document.embedding));
+        self.documents.insert(document.id, document.clone());
+    }
+
+    fn clear(&mut self) {
+        self.documents.clear();
+        self.unit_embeddings.clear();
+        self.parents.clear();
+    }
+
+    /// Removes the chunks of `source` and their parent sections
+    fn remove_source(&mut self, source: &str) -> u64 {
+        let removed: Vec<Document> = self
+            .documents
+            .values()
+            .filter(|doc| doc.source() == source)
+            .cloned()
+            .collect();
+        for document in &removed {
+            self.documents.remove(&document.id);
+            self.unit_embeddings.remove(&document.id);
+            if let Some(parent_id) = document.parent_id {
+                self.parents.remove(&parent_id);
+            }
+        }
+        removed.len() as u64
+    }
+}
+
+/// Summary of one source file's chunks, mirroring the Postgres aggregate
+fn document_info(source: &str, chunks: &[&Document]) -> DocumentInfo {
+    DocumentInfo {
+        source: source.to_string(),
+        file_name: chunks
+            .iter()
+            .map(|doc| doc.file_name.clone())
+            .min()
+            .unwrap_or_default(),
+        content_hash: chunks
+            .iter()
+            .filter_map(|doc| doc.metadata.checksum.clone())
+            .max(),
+        chunks: chunks.len() as i64,
+        ingested_at: chunks
+            .iter()
+            .map(|doc| doc.created_at)
+            .max()
+            .unwrap_or_default(),
+        modified_at: chunks.iter().filter_map(|doc| doc.metadata.mtime).max(),
+    }
+}
+
+/// `vector` scaled to unit length; the zero vector stays zero
+fn normalized(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|x| x / norm).collect()
+}
+
+/// Cosine similarity of two unit-length (or zero) vectors
+fn cosine_similarity(query: &[f32], embedding: &[f32]) -> f32 {
+    query.iter().zip(embedding).map(|(q, e)| q * e).sum()
+}
+
+impl Default for InMemoryVectorStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryVectorStore {
+    pub fn new() -> Self {
+        // Dimensions are not enforced in memory
+        let store = Self::empty();
+        store.open(&Collection::new(DEFAULT_COLLECTION, UNKNOWN_MODEL, 0));
+        store
+    }
+
+    /// A store without any collection, reading and writing the default one once opened
+    pub(crate) fn empty() -> Self {
+        Self {
+            collection: DEFAULT_COLLECTION.to_string(),
+            collections: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// A store for `collection` sharing this store's data, creating the
+    /// collection unvalidated on first use
+    pub(crate) fn open(&self, collection: &Collection) -> Self {
+        self.collections
+            .lock()
+            .unwrap()
+            .entry(collection.name.clone())
+            .or_insert_with(|| InMemoryCollection::new(collection.clone()));
+        Self {
+            collection: collection.name.clone(),
+            collections: Arc::clone(&self.collections),
+        }
+    }
+
+    /// Adds already stored parent sections and chunks, keeping their ids
+    pub(crate) fn insert_stored(
+        &self,
+        parents: &[ParentDocument],
+        documents: &[Document],
+    ) -> Result<()> {
+        self.with_collection(|collection| {
+            for parent in parents {
+                collection.parents.insert(parent.id, parent.clone());
+            }
+            for document in documents {
+                collection.insert_document(document);
+            }
+        })
+    }
+
+    /// Replaces the chunks and parent sections of `source`, keeping the new ids
+    pub(crate) fn replace_source(
+        &self,
+        source: &str,
+        parents: &[ParentDocument],
+        documents: &[Document],
+    ) -> Result<()> {
+        self.with_collection(|collection| {
+            collection.remove_source(source);
+            for parent in parents {
+                collection.parents.insert(parent.id, parent.clone());
+            }
+            for document in documents {
+                collection.insert_document(document);
+            }
+        })
+    }
+
+    fn with_collection<T>(&self, f: impl FnOnce(&mut InMemoryCollection) -> T) -> Result<T> {
+        let mut collections = self.collections.lock().unwrap();
+        let collection = collections
+            .get_mut(&self.collection)
+            .ok_or_else(|| anyhow::anyhow!("Collection '{}' does not exist", self.collection))?;
+        Ok(f(collection))
+    }
+}
+
+#[async_trait]
+impl VectorStore for InMemoryVectorStore {
+    fn collection_name(&self) -> &str {
+        &self.collection
+    }
+
+    async fn insert_document(&self, chunk: DocumentChunk) -> Result<Document> {
+        let document = chunk.into_document();
+        self.insert_stored(&[], std::slice::from_ref(&document))?;
+        Ok(document)
+    }
+
+    async fn insert_documents_batch(&self, chunks: Vec<DocumentChunk>) -> Result<Vec<Document>> {
+        let documents: Vec<Document> = chunks
+            .into_iter()
+            .map(DocumentChunk::into_document)
+            .collect();
+        self.insert_stored(&[], &documents)?;
+        Ok(documents)
+    }
+
+    async fn insert_parent(&self, parent: &ParentDocument) -> Result<()> {
+        self.insert_stored(std::slice::from_ref(parent), &[])
+    }
+
+    async fn upsert_document(
+        &self,
+        source: &str,
+        parents: &[ParentDocument],
+        chunks: Vec<DocumentChunk>,
+    ) -> Result<Vec<Document>> {
+        let documents: Vec<Document> = chunks
+            .into_iter()
+            .map(DocumentChunk::into_document)
+            .collect();
+        if let Some(document) = documents.iter().find(|doc| doc.source() != source) {
+            anyhow::bail!(
+                "Chunk of '{}' cannot be stored as part of '{}'",
+                document.source(),
+                source
+            );
+        }
+
+        self.replace_source(source, parents, &documents)?;
+        Ok(documents)
+    }
+
+    /// Scores the chunks in place under the lock; only the returned results are cloned
+    async fn search_similar(
+        &self,
+        query_embedding: Vec<f32>,
+        limit: usize,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        let query = normalized(&query_embedding);
+        // Same candidate pools as the Postgres store: over-fetch for parent
+        // deduplication, and again for each hybrid leg
+        let fetch_limit = if options.expand_parents {
+            limit.saturating_mul(3)
+        } else {
+            limit
+        };
+
+        self.with_collection(|collection| {
+            if let Some(document) = collection
+                .documents
+                .values()
+                .find(|doc| doc.embedding.len() != query.len())
+            {
+                anyhow::bail!(
+                    "Query has {} embedding dimensions but the collection stores {}",
+                    query.len(),
+                    document.embedding.len()
+                );
+            }
+
+            let mut candidates: Vec<(&Document, f32)> = collection
+                .documents
+                .values()
+                .filter(|doc| {
+                    options
+                        .filter
+                        .as_ref()
+                        .is_none_or(|filter| filter.matches(&doc.metadata.to_value()))
+                })
+                .map(|doc| {
+                    let similarity = collection
+                        .unit_embeddings
+                        .get(&doc.id)
+                        .map_or(0.0, |unit| cosine_similarity(&query, unit));
+                    (doc, similarity)
+                })
+                .collect();
+            // Best first; ties in document order so results are deterministic
+            candidates.sort_by(|(a, a_similarity), (b, b_similarity)| {
+                b_similarity
+                    .total_cmp(a_similarity)
+                    .then_with(|| (&a.file_name, a.chunk_id).cmp(&(&b.file_name, b.chunk_id)))
+            });
+
+            let result =
+                |&(doc, similarity): &(&Document, f32)| SearchResult::new(doc.clone(), similarity);
+            let vector_hits = candidates
+                .iter()
+                .filter(|(_, similarity)| *similarity > MIN_SIMILARITY);
+
+            let ranked: Vec<SearchResult> = match &options.hybrid {
+                None => vector_hits.take(fetch_limit).map(result).collect(),
+                Some(hybrid) => {
+                    let leg_limit = fetch_limit.saturating_mul(2);
+                    let contents: Vec<&str> = candidates
+                        .iter()
+                        .map(|(doc, _)| doc.content.as_str())
+                        .collect();
+                    let mut lexical: Vec<(f32, &(&Document, f32))> =
+                        bm25_scores(&hybrid.query_text, &contents)
+                            .into_iter()
+                            .zip(&candidates)
+                            .filter(|(score, _)| *score > 0.0)
+                            .collect();
+                    lexical.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+                    hybrid.fuse(
+                        vector_hits.take(leg_limit).map(result).collect(),
+                        lexical
+                            .into_iter()
+                            .take(leg_limit)
+                            .map(|(_, candidate)| result(candidate))
+                            .collect(),
+                    )
+                }
+            };
+
+            let results: Vec<SearchResult> = ranked
+                .into_iter()
+                .map(|result| {
+                    match result
+                        .document
+                        .parent_id
+                        .and_then(|id| collection.parents.get(&id))
+                    {
+                        Some(parent) if options.expand_parents => {
+                            result.with_parent(parent.clone())
+                        }
+                        _ => result,
+                    }
+                })
+                .collect();
+
+            let mut results = if options.expand_parents {
+                dedupe_by_parent(results)
+            } else {
+                results
+            };
+            results.truncate(limit);
+            Ok(results)
+        })?
+    }
+
+    async fn delete_by_file(&self, source: &str) -> Result<u64> {
+        self.with_collection(|collection| collection.remove_source(source))
+    }
+
+    async fn delete_all_documents(&self) -> Result<()> {
+        self.with_collection(InMemoryCollection::clear)
+    }
+
+    async fn list_documents(&self) -> Result<Vec<DocumentInfo>> {
+        self.with_collection(|collection| {
+            let mut sources: BTreeMap<&str, Vec<&Document>> = BTreeMap::new();
+            for document in collection.documents.values() {
+                sources.entry(document.source()).or_default().push(document);
+            }
+            sources
+                .into_iter()
+                .map(|(source, chunks)| document_info(source, &chunks))
+                .collect()
+        })
+    }
+
+    async fn get_document(&self, source: &str) -> Result<Option<DocumentInfo>> {
+        let documents = self.list_documents().await?;
+        Ok(documents.into_iter().find(|info| info.source == source))
+    }
+
+    async fn get_chunks(&self, source: &str) -> Result<Vec<Document>> {
+        let mut chunks: Vec<Document> = self.with_collection(|collection| {
+            collection
+                .documents
+                .values()
+                .filter(|doc| doc.source() == source)
+                .cloned()
+                .collect()
+        })?;
+        chunks.sort_by_key(|doc| doc.chunk_id);
+        Ok(chunks)
+    }
+
+    async fn get_document_count(&self) -> Result<i64> {
+        self.with_collection(|collection| collection.documents.len() as i64)
+    }
+
+    async fn collection_stats(&self) -> Result<CollectionStats> {
+        self.with_collection(|collection| {
+            let files: HashSet<&str> = collection
+                .documents
+                .values()
+                .map(|doc| doc.source())
+                .collect();
+            CollectionStats {
+                name: collection.info.name.clone(),
+                embedding_model: collection.info.embedding_model.clone(),
+                dimensions: collection.info.dimensions,
+                documents: collection.documents.len() as i64,
+                files: files.len() as i64,
+                parents: collection.parents.len() as i64,
+            }
+        })
+    }
+
+    async fn collection_store(&self, collection: &Collection) -> Result<Arc<dyn VectorStore>> {
+        if collection.name != DEFAULT_COLLECTION {
+            collection.validate()?;
+        }
+        Ok(Arc::new(self.open(collection)))
+    }
+
+    async fn list_collections(&self) -> Result<Vec<Collection>> {
+        let mut collections: Vec<Collection> = self
+            .collections
+            .lock()
+            .unwrap()
+            .values()
+            .map(|collection| collection.info.clone())
+            .collect();
+        collections.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(collections)
+    }
+
+    async fn drop_collection(&self, name: &str) -> Result<()> {
+        self.collections
+            .lock()
+            .unwrap()
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| anyhow::anyhow!("Collection '{}' does not exist", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance;
+    use crate::filter::MetadataFilter;
+    use crate::hybrid::HybridSearch;
+    use crate::models::DocumentMetadata;
+
+    #[tokio::test]
+    async fn should_pass_conformance_suite() {
+        conformance::run(&InMemoryVectorStore::new(), 4)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_reject_query_with_other_dimension() {
+        let store = InMemoryVectorStore::new();
+        store
+            .insert_document(DocumentChunk::new(
+                "a.md".to_string(),
+                0,
+                "A".to_string(),
+                vec![0.1; 4],
+            ))
+            .await
+            .unwrap();
+
+        let error = store
+            .search_similar(vec![0.1; 8], 5, &SearchOptions::default())
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("embedding dimensions"));
+    }
+
+    #[test]
+    fn should_compute_cosine_similarity() {
+        let query = normalized(&[3.0, 4.0]);
+
+        assert!((cosine_similarity(&query, &normalized(&[6.0, 8.0])) - 1.0).abs() < 1e-6);
+        assert!((cosine_similarity(&query, &normalized(&[-3.0, -4.0])) + 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&query, &normalized(&[0.0, 0.0])), 0.0);
+        assert_eq!(normalized(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn should_expand_hits_to_deduplicated_parents() {
+        let store = InMemoryVectorStore::new();
+        let parent = ParentDocument::new(
+            "handbook.md".to_string(),
+            "Submit receipts. Within thirty days.".to_string(),
+            vec![],
+        );
+        store.insert_parent(&parent).await.unwrap();
+        for (chunk_id, content) in ["Submit receipts.", "Within thirty days."]
+            .into_iter()
+            .enumerate()
+        {
+            let chunk = DocumentChunk::new(
+                "handbook.md".to_string(),
+                chunk_id,
+                content.to_string(),
+                vec![0.1; 4],
+            )
+            .with_parent(parent.id);
+            store.insert_document(chunk).await.unwrap();
+        }
+
+        let expanded = store
+            .search_similar(
+                vec![0.1; 4],
+                5,
+                &SearchOptions {
+                    expand_parents: true,
+                    ..SearchOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+        let plain = store
+            .search_similar(vec![0.1; 4], 5, &SearchOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(expanded.len(), 1);
+        assert_eq!(expanded[0].context(), parent.content);
+        assert_eq!(plain.len(), 2);
+        assert!(plain.iter().all(|result| result.parent.is_none()));
+    }
+
+    #[tokio::test]
+    async fn should_filter_search_by_metadata() {
+        let store = InMemoryVectorStore::new();
+        for (file_name, department) in [("leave.md", "HR"), ("vpn.md", "IT")] {
+            let metadata = DocumentMetadata {
+                department: Some(department.to_string()),
+                tags: vec!["policy".to_string()],
+                ..Default::default()
+            };
+            let chunk = DocumentChunk::new(
+                file_name.to_string(),
+                0,
+                format!("{} content", file_name),
+                vec![0.1; 4],
+            )
+            .with_metadata(metadata);
+            store.insert_document(chunk).await.unwrap();
+        }
+
+        let options = SearchOptions {
+            filter: Some(MetadataFilter::parse("department = 'HR' AND tags ? 'policy'").unwrap()),
+            ..SearchOptions::default()
+        };
+        let results = store
+            .search_similar(vec![0.1; 4], 5, &options)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.file_name, "leave.md");
+    }
+
+    #[tokio::test]
+    async fn should_rank_exact_term_matches_first_in_hybrid_search() {
+        let store = InMemoryVectorStore::new();
+        for (file_name, content) in [
+            ("a_general.md", "Leave can be booked in the HR tool."),
+            ("b_travel.md", "Travel bookings need manager approval."),
+            (
+                "c_policy.md",
+                "Policy POL-4471 sets the parental leave allowance.",
+            ),
+        ] {
+            let chunk =
+                DocumentChunk::new(file_name.to_string(), 0, content.to_string(), vec![0.1; 4]);
+            store.insert_document(chunk).await.unwrap();
+        }
+
+        let vector_only = store
+            .search_similar(vec![0.1; 4], 3, &SearchOptions::default())
+            .await
+            .unwrap();
+        let hybrid = store
+            .search_similar(
+                vec![0.1; 4],
+                3,
+                &SearchOptions {
+                    hybrid: Some(HybridSearch::new("What does POL-4471 say?")),
+                    ..SearchOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(vector_only[0].document.file_name, "a_general.md");
+        assert_eq!(hybrid[0].document.file_name, "c_policy.md");
+        assert_eq!(hybrid.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn should_keep_collections_separate() {
+        let default_store = InMemoryVectorStore::new();
+        let hr_store = default_store
+            .collection_store(&Collection::new("hr", "test-model", 4))
+            .await
+            .unwrap();
+        default_store
+            .insert_document(DocumentChunk::new(
+                "readme.md".to_string(),
+                0,
+                "General notes".to_string(),
+                vec![0.1; 4],
+            ))
+            .await
+            .unwrap();
+        for chunk_id in 0..2 {
+            hr_store
+                .insert_document(DocumentChunk::new(
+                    "leave.md".to_string(),
+                    chunk_id,
+                    "Leave policy".to_string(),
+                    vec![0.1; 4],
+                ))
+                .await
+                .unwrap();
+        }
+
+        let hr_results = hr_store
+            .search_similar(vec![0.1; 4], 5, &SearchOptions::default())
+            .await
+            .unwrap();
+        let stats = hr_store.collection_stats().await.unwrap();
+        let names: Vec<String> = default_store
+            .list_collections()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|collection| collection.name)
+            .collect();
+
+        assert_eq!(hr_results.len(), 2);
+        assert!(hr_results
+            .iter()
+            .all(|r| r.document.file_name == "leave.md"));
+        assert_eq!((stats.documents, stats.files, stats.parents), (2, 1, 0));
+        assert_eq!(names, vec!["default".to_string(), "hr".to_string()]);
+
+        default_store.drop_collection("hr").await.unwrap();
+
+        assert!(hr_store
+            .search_similar(vec![0.1; 4], 5, &SearchOptions::default())
+            .await
+            .is_err());
+        assert!(default_store.drop_collection("hr").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_replace_file_chunks_on_upsert() {
+        let store = InMemoryVectorStore::new();
+        let chunks = |version: &str, count: usize| -> Vec<DocumentChunk> {
+            let metadata = DocumentMetadata {
+                source_path: Some("docs/leave.md".to_string()),
+                checksum: Some(version.to_string()),
+                ..Default::default()
+            };
+            (0..count)
+                .map(|chunk_id| {
+                    DocumentChunk::new(
+                        "leave.md".to_string(),
+                        chunk_id,
+                        format!("{} chunk {}", version, chunk_id),
+                        vec![0.1; 4],
+                    )
+                    .with_metadata(metadata.clone())
+                })
+                .collect()
+        };
+        let parent = ParentDocument::new("leave.md".to_string(), "v1".to_string(), vec![]);
+
+        let chunks_v1: Vec<DocumentChunk> = chunks("v1", 3)
+            .into_iter()
+            .map(|chunk| chunk.with_parent(parent.id))
+            .collect();
+        store
+            .upsert_document("docs/leave.md", &[parent], chunks_v1)
+            .await
+            .unwrap();
+        store
+            .upsert_document("docs/leave.md", &[], chunks("v2", 2))
+            .await
+            .unwrap();
+        store
+            .insert_document(DocumentChunk::new(
+                "notes.txt".to_string(),
+                0,
+                "Notes".to_string(),
+                vec![0.1; 4],
+            ))
+            .await
+            .unwrap();
+
+        let documents = store.list_documents().await.unwrap();
+        let chunks_v2 = store.get_chunks("docs/leave.md").await.unwrap();
+
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].source, "docs/leave.md");
+        assert_eq!(documents[0].chunks, 2);
+        assert_eq!(documents[0].content_hash.as_deref(), Some("v2"));
+        assert_eq!(documents[1].source, "notes.txt");
+        let contents: Vec<&str> = chunks_v2.iter().map(|doc| doc.content.as_str()).collect();
+        assert_eq!(contents, vec!["v2 chunk 0", "v2 chunk 1"]);
+        assert_eq!(store.collection_stats().await.unwrap().parents, 0);
+
+        assert_eq!(store.delete_by_file("docs/leave.md").await.unwrap(), 2);
+        assert!(store.get_document("docs/leave.md").await.unwrap().is_none());
+        assert!(store
+            .upsert_document("other.md", &[], chunks("v3", 1))
+            .await
+            .is_err());
+    }
+}
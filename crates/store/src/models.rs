@@ -2,6 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::filter::MetadataFilter;
+use crate::hybrid::HybridSearch;
+
 // Core types moved from agentic-core crate
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Role {
@@ -48,6 +51,48 @@ impl SessionData {
     }
 }
 
+/// Location of a chunk in its source file, used to highlight cited passages
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start_char: usize,
+    pub end_char: usize,
+    /// 1-based, inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Descriptive metadata stored with every chunk of a document and used for filtering
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DocumentMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub department: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// Headings enclosing the chunk in its source file, outermost first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub heading_path: Vec<String>,
+    /// Any other keys from sidecar files or front matter
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl DocumentMetadata {
+    /// The metadata as a JSON object, as stored in the `metadata` column
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Document {
     pub id: Uuid,
@@ -56,6 +101,14 @@ pub struct Document {
     pub content: String,
     pub embedding: Vec<f32>,
     pub created_at: DateTime<Utc>,
+    /// `None` for documents ingested before spans were recorded
+    #[serde(default)]
+    pub span: Option<SourceSpan>,
+    /// Section this chunk was cut from, see [`ParentDocument`]
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub metadata: DocumentMetadata,
 }
 
 impl Document {
@@ -67,8 +120,64 @@ impl Document {
             content,
             embedding,
             created_at: Utc::now(),
+            span: None,
+            parent_id: None,
+            metadata: DocumentMetadata::default(),
+        }
+    }
+
+    /// Identity of the source file: its path when known, otherwise its file name
+    pub fn source(&self) -> &str {
+        self.metadata
+            .source_path
+            .as_deref()
+            .unwrap_or(&self.file_name)
+    }
+}
+
+/// A stored source file: its identity, content hash, chunk count and timestamps
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DocumentInfo {
+    /// Source path, or the file name for documents added without one
+    pub source: String,
+    pub file_name: String,
+    /// Checksum of the ingested content; unchanged files need no re-embedding
+    pub content_hash: Option<String>,
+    pub chunks: i64,
+    /// When the current chunks were stored
+    pub ingested_at: DateTime<Utc>,
+    /// Modification time of the source file at ingestion
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+/// A larger section of a source file whose child chunks are embedded separately,
+/// returned in place of the child to give the LLM surrounding context
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParentDocument {
+    pub id: Uuid,
+    pub file_name: String,
+    pub content: String,
+    pub heading_path: Vec<String>,
+    pub span: Option<SourceSpan>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ParentDocument {
+    pub fn new(file_name: String, content: String, heading_path: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            file_name,
+            content,
+            heading_path,
+            span: None,
+            created_at: Utc::now(),
         }
     }
+
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +186,9 @@ pub struct DocumentChunk {
     pub chunk_id: usize,
     pub content: String,
     pub embedding: Vec<f32>,
+    pub span: Option<SourceSpan>,
+    pub parent_id: Option<Uuid>,
+    pub metadata: DocumentMetadata,
 }
 
 impl DocumentChunk {
@@ -86,11 +198,34 @@ impl DocumentChunk {
             chunk_id,
             content,
             embedding,
+            span: None,
+            parent_id: None,
+            metadata: DocumentMetadata::default(),
         }
     }
 
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_parent(mut self, parent_id: Uuid) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: DocumentMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     pub fn into_document(self) -> Document {
-        Document::new(self.file_name, self.chunk_id, self.content, self.embedding)
+        Document {
+            span: self.span,
+            parent_id: self.parent_id,
+            metadata: self.metadata,
+            ..Document::new(self.file_name, self.chunk_id, self.content, self.embedding)
+        }
     }
 }
 
@@ -98,6 +233,12 @@ impl DocumentChunk {
 pub struct SearchResult {
     pub document: Document,
     pub similarity: f32,
+    /// Enclosing section, populated when searching with `expand_parents`
+    #[serde(default)]
+    pub parent: Option<ParentDocument>,
+    /// Relevance assigned by a reranker, when one ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
 }
 
 impl SearchResult {
@@ -105,8 +246,52 @@ impl SearchResult {
         Self {
             document,
             similarity,
+            parent: None,
+            rerank_score: None,
         }
     }
+
+    /// Relevance used to rank the result: the rerank score when reranked,
+    /// otherwise the similarity
+    pub fn relevance(&self) -> f32 {
+        self.rerank_score.unwrap_or(self.similarity)
+    }
+
+    pub fn with_parent(mut self, parent: ParentDocument) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Text to show the LLM: the parent section when expanded, otherwise the chunk
+    pub fn context(&self) -> &str {
+        self.parent
+            .as_ref()
+            .map_or(&self.document.content, |parent| &parent.content)
+    }
+}
+
+/// Options for similarity search
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchOptions {
+    /// Return each hit's parent section, keeping only the best hit per parent
+    pub expand_parents: bool,
+    /// Only return documents whose metadata matches
+    pub filter: Option<MetadataFilter>,
+    /// Fuse full-text ranking with vector ranking instead of pure vector search
+    pub hybrid: Option<HybridSearch>,
+}
+
+/// Drops hits whose parent section was already returned by a better-ranked hit.
+/// Expects results ordered by descending similarity.
+pub fn dedupe_by_parent(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut seen = std::collections::HashSet::new();
+    results
+        .into_iter()
+        .filter(|result| match &result.parent {
+            Some(parent) => seen.insert(parent.id),
+            None => true,
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -252,6 +437,40 @@ mod tests {
         assert!(!doc.id.is_nil());
     }
 
+    #[test]
+    fn should_carry_source_span_into_document() {
+        let span = SourceSpan {
+            start_byte: 10,
+            end_byte: 24,
+            start_char: 9,
+            end_char: 23,
+            start_line: 2,
+            end_line: 3,
+        };
+        let chunk = DocumentChunk::new(
+            "test.md".to_string(),
+            0,
+            "chunk content".to_string(),
+            vec![0.1, 0.2],
+        )
+        .with_span(span);
+
+        let doc = chunk.into_document();
+
+        assert_eq!(doc.span, Some(span));
+    }
+
+    #[test]
+    fn should_deserialize_document_without_span() {
+        let doc = Document::new("a.txt".to_string(), 0, "text".to_string(), vec![0.5]);
+        let mut json = serde_json::to_value(&doc).unwrap();
+        json.as_object_mut().unwrap().remove("span");
+
+        let deserialized: Document = serde_json::from_value(json).unwrap();
+
+        assert_eq!(deserialized.span, None);
+    }
+
     #[test]
     fn should_create_search_result() {
         let embedding = vec![0.1, 0.2, 0.3];
@@ -268,6 +487,50 @@ mod tests {
         assert_eq!(result.similarity, 0.95);
     }
 
+    #[test]
+    fn should_keep_best_hit_per_parent() {
+        let parent = ParentDocument::new(
+            "handbook.md".to_string(),
+            "## Expenses\nSubmit receipts. Use the portal.".to_string(),
+            vec!["Expenses".to_string()],
+        );
+        let child = |content: &str, similarity| {
+            SearchResult::new(
+                Document::new("handbook.md".to_string(), 0, content.to_string(), vec![]),
+                similarity,
+            )
+        };
+        let results = vec![
+            child("Submit receipts.", 0.9).with_parent(parent.clone()),
+            child("Unrelated", 0.8),
+            child("Use the portal.", 0.7).with_parent(parent.clone()),
+        ];
+
+        let deduped = dedupe_by_parent(results);
+
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].context(), parent.content);
+        assert_eq!(deduped[1].context(), "Unrelated");
+    }
+
+    #[test]
+    fn should_flatten_extra_metadata_keys() {
+        let metadata: DocumentMetadata = serde_json::from_value(serde_json::json!({
+            "department": "HR",
+            "tags": ["policy"],
+            "owner": "people-ops"
+        }))
+        .unwrap();
+
+        assert_eq!(metadata.department.as_deref(), Some("HR"));
+        assert_eq!(metadata.tags, vec!["policy".to_string()]);
+        assert_eq!(metadata.extra["owner"], "people-ops");
+        assert_eq!(
+            metadata.to_value(),
+            serde_json::json!({"department": "HR", "tags": ["policy"], "owner": "people-ops"})
+        );
+    }
+
     #[test]
     fn should_serialize_and_deserialize_document() {
         let embedding = vec![0.1, 0.2, 0.3];
@@ -0,0 +1,1060 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use pgvector::Vector;
+use sqlx::{
+    postgres::{PgPoolOptions, PgRow},
+    types::Json,
+    Executor, PgPool, Postgres, QueryBuilder, Row, Transaction,
+};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing;
+
+use crate::collection::{Collection, CollectionStats, DEFAULT_COLLECTION, UNKNOWN_MODEL};
+use crate::filter::FilterParam;
+use crate::hybrid::lexical_terms;
+use crate::index::VectorIndexConfig;
+use crate::models::{
+    dedupe_by_parent, Document, DocumentChunk, DocumentInfo, DocumentMetadata, ParentDocument,
+    SearchOptions, SearchResult, SourceSpan,
+};
+use crate::vector_store::{VectorStore, MIN_SIMILARITY};
+
+/// Rows per multi-row insert; 15 parameters each stays well below Postgres' 65535 limit
+const INSERT_BATCH_SIZE: usize = 500;
+
+/// SQL identity of a chunk's source file, see [`Document::source`]
+const SOURCE_SQL: &str = "COALESCE(metadata->>'source_path', file_name)";
+
+/// Which ranking a search query uses
+#[derive(Clone, Copy)]
+enum SearchLeg<'a> {
+    Vector,
+    /// Full-text ranking for a `websearch_to_tsquery` query
+    Lexical(&'a str),
+}
+
+pub struct PgVectorStore {
+    pool: PgPool,
+    /// Collection this store reads and writes
+    collection: Collection,
+    index: VectorIndexConfig,
+}
+
+impl PgVectorStore {
+    pub async fn new(database_url: &str) -> Result<Self> {
+        Self::new_with_dimensions(database_url, 1024).await
+    }
+
+    pub async fn new_with_dimensions(
+        database_url: &str,
+        embedding_dimensions: usize,
+    ) -> Result<Self> {
+        Self::new_with_index(
+            database_url,
+            embedding_dimensions,
+            VectorIndexConfig::default(),
+        )
+        .await
+    }
+
+    pub async fn new_with_index(
+        database_url: &str,
+        embedding_dimensions: usize,
+        index: VectorIndexConfig,
+    ) -> Result<Self> {
+        let collection = Collection::new(DEFAULT_COLLECTION, UNKNOWN_MODEL, embedding_dimensions);
+        Self::new_with_collection(database_url, collection, index).await
+    }
+
+    /// Connects to `collection`, creating it and its vector index on first use
+    pub async fn new_with_collection(
+        database_url: &str,
+        collection: Collection,
+        index: VectorIndexConfig,
+    ) -> Result<Self> {
+        collection.validate()?;
+
+        let search_settings = index.search_settings_sql();
+        let pool = PgPoolOptions::new()
+            .after_connect(move |conn, _meta| {
+                let search_settings = search_settings.clone();
+                Box::pin(async move {
+                    conn.execute(search_settings.as_str()).await?;
+                    Ok(())
+                })
+            })
+            .connect(database_url)
+            .await
+            .context("Failed to connect to PostgreSQL")?;
+
+        crate::run_migrations(database_url)
+            .await
+            .context("Failed to run database migrations")?;
+
+        let mut store = Self {
+            pool,
+            collection,
+            index,
+        };
+        store.collection = store.create_collection(&store.collection).await?;
+
+        tracing::info!(
+            "Vector store initialization completed successfully with migrations applied"
+        );
+
+        Ok(store)
+    }
+
+    /// The collection this store reads and writes
+    pub fn collection(&self) -> &Collection {
+        &self.collection
+    }
+
+    /// Registers `collection` and builds its vector index. An existing collection
+    /// is reused when its dimension and model match, and rejected otherwise.
+    pub async fn create_collection(&self, collection: &Collection) -> Result<Collection> {
+        collection.validate()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO collections (name, embedding_model, dimensions)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (name) DO NOTHING
+            "#,
+        )
+        .bind(&collection.name)
+        .bind(&collection.embedding_model)
+        .bind(collection.dimensions as i32)
+        .execute(&self.pool)
+        .await
+        .context("Failed to register collection")?;
+
+        let stored = self
+            .get_collection(&collection.name)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!("Collection '{}' was not registered", collection.name)
+            })?;
+
+        stored.ensure_compatible(collection)?;
+
+        self.ensure_index(&stored).await?;
+        Ok(stored)
+    }
+
+    pub async fn get_collection(&self, name: &str) -> Result<Option<Collection>> {
+        let row = sqlx::query(
+            "SELECT name, embedding_model, dimensions, created_at FROM collections WHERE name = $1",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to read collection")?;
+
+        Ok(row.as_ref().map(collection_from_row))
+    }
+
+    /// Creates the collection's vector index, rebuilding it when its type,
+    /// parameters or metric differ from the configured ones
+    pub async fn ensure_index(&self, collection: &Collection) -> Result<()> {
+        let row = sqlx::query(
+            r#"
+            SELECT am.amname, opc.opcname, COALESCE(c.reloptions, '{}') AS reloptions
+            FROM pg_class c
+            JOIN pg_am am ON am.oid = c.relam
+            JOIN pg_index i ON i.indexrelid = c.oid
+            JOIN pg_opclass opc ON opc.oid = i.indclass[0]
+            WHERE c.relname = $1
+            "#,
+        )
+        .bind(collection.index_name())
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to inspect embedding index")?;
+
+        let matches = row.is_some_and(|row| {
+            let access_method: String = row.get("amname");
+            let operator_class: String = row.get("opcname");
+            let mut options: Vec<String> = row.get("reloptions");
+            let mut expected = self.index.storage_options();
+            options.sort();
+            expected.sort();
+            access_method == self.index.access_method()
+                && operator_class == self.index.metric.operator_class()
+                && options == expected
+        });
+
+        if matches {
+            tracing::info!(
+                "Embedding index of collection '{}' is up to date",
+                collection.name
+            );
+            Ok(())
+        } else {
+            self.rebuild_index(collection).await
+        }
+    }
+
+    /// Drops and recreates the collection's vector index from the configuration
+    pub async fn rebuild_index(&self, collection: &Collection) -> Result<()> {
+        let create_sql = self.index.create_index_sql(collection);
+        tracing::info!("Rebuilding embedding index: {}", create_sql);
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!("DROP INDEX IF EXISTS {}", collection.index_name()))
+            .execute(&mut *tx)
+            .await
+            .context("Failed to drop embedding index")?;
+        sqlx::query(&create_sql)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create embedding index")?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_source(&self, tx: &mut Transaction<'_, Postgres>, source: &str) -> Result<u64> {
+        sqlx::query(&format!(
+            r#"
+            DELETE FROM document_parents
+            WHERE id IN (
+                SELECT parent_id FROM documents WHERE collection = $1 AND {} = $2
+            )
+            "#,
+            SOURCE_SQL
+        ))
+        .bind(&self.collection.name)
+        .bind(source)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to delete parent sections")?;
+
+        let deleted = sqlx::query(&format!(
+            "DELETE FROM documents WHERE collection = $1 AND {} = $2",
+            SOURCE_SQL
+        ))
+        .bind(&self.collection.name)
+        .bind(source)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to delete documents")?;
+
+        Ok(deleted.rows_affected())
+    }
+
+    async fn insert_document_row<'e, E>(&self, executor: E, document: &Document) -> Result<()>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let embedding_vector = Vector::from(document.embedding.clone());
+        let span = document.span.as_ref();
+        let span_field = |field: fn(&SourceSpan) -> usize| span.map(|span| field(span) as i32);
+
+        sqlx::query(
+            r#"
+            INSERT INTO documents (
+                id, file_name, chunk_id, content, embedding, created_at,
+                start_byte, end_byte, start_char, end_char, start_line, end_line, parent_id,
+                metadata, collection
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            "#,
+        )
+        .bind(document.id)
+        .bind(&document.file_name)
+        .bind(document.chunk_id as i32)
+        .bind(&document.content)
+        .bind(embedding_vector)
+        .bind(document.created_at)
+        .bind(span_field(|s| s.start_byte))
+        .bind(span_field(|s| s.end_byte))
+        .bind(span_field(|s| s.start_char))
+        .bind(span_field(|s| s.end_char))
+        .bind(span_field(|s| s.start_line))
+        .bind(span_field(|s| s.end_line))
+        .bind(document.parent_id)
+        .bind(Json(&document.metadata))
+        .bind(&self.collection.name)
+        .execute(executor)
+        .await
+        .context("Failed to insert document")?;
+
+        Ok(())
+    }
+
+    /// Multi-row inserts of up to [`INSERT_BATCH_SIZE`] chunks, logging each batch's throughput
+    async fn insert_document_rows(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        documents: &[Document],
+    ) -> Result<()> {
+        for batch in documents.chunks(INSERT_BATCH_SIZE) {
+            let started = Instant::now();
+            let mut query = QueryBuilder::<Postgres>::new(
+                r#"
+                INSERT INTO documents (
+                    id, file_name, chunk_id, content, embedding, created_at,
+                    start_byte, end_byte, start_char, end_char, start_line, end_line, parent_id,
+                    metadata, collection
+                )
+                "#,
+            );
+            query.push_values(batch, |mut row, document| {
+                let span = document.span.as_ref();
+                let span_field =
+                    |field: fn(&SourceSpan) -> usize| span.map(|span| field(span) as i32);
+                row.push_bind(document.id)
+                    .push_bind(document.file_name.clone())
+                    .push_bind(document.chunk_id as i32)
+                    .push_bind(document.content.clone())
+                    .push_bind(Vector::from(document.embedding.clone()))
+                    .push_bind(document.created_at)
+                    .push_bind(span_field(|s| s.start_byte))
+                    .push_bind(span_field(|s| s.end_byte))
+                    .push_bind(span_field(|s| s.start_char))
+                    .push_bind(span_field(|s| s.end_char))
+                    .push_bind(span_field(|s| s.start_line))
+                    .push_bind(span_field(|s| s.end_line))
+                    .push_bind(document.parent_id)
+                    .push_bind(Json(document.metadata.clone()))
+                    .push_bind(self.collection.name.clone());
+            });
+            query
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("Failed to insert document batch")?;
+
+            let elapsed = started.elapsed();
+            tracing::info!(
+                "Inserted {} chunks in {:.1} ms ({:.0} chunks/s)",
+                batch.len(),
+                elapsed.as_secs_f64() * 1000.0,
+                batch.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn insert_parent_row<'e, E>(&self, executor: E, parent: &ParentDocument) -> Result<()>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let span = parent.span.as_ref();
+        let span_field = |field: fn(&SourceSpan) -> usize| span.map(|span| field(span) as i32);
+
+        sqlx::query(
+            r#"
+            INSERT INTO document_parents (
+                id, file_name, content, heading_path, created_at,
+                start_byte, end_byte, start_char, end_char, start_line, end_line, collection
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(parent.id)
+        .bind(&parent.file_name)
+        .bind(&parent.content)
+        .bind(&parent.heading_path)
+        .bind(parent.created_at)
+        .bind(span_field(|s| s.start_byte))
+        .bind(span_field(|s| s.end_byte))
+        .bind(span_field(|s| s.start_char))
+        .bind(span_field(|s| s.end_char))
+        .bind(span_field(|s| s.start_line))
+        .bind(span_field(|s| s.end_line))
+        .bind(&self.collection.name)
+        .execute(executor)
+        .await
+        .context("Failed to insert parent section")?;
+
+        Ok(())
+    }
+
+    /// Runs a single ranked query; hits carry their cosine similarity whichever leg found them
+    async fn run_search(
+        &self,
+        leg: SearchLeg<'_>,
+        query_vector: &Vector,
+        limit: usize,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        let metric = self.index.metric;
+        let embedding = self.collection.embedding_sql();
+        let similarity = metric.similarity_sql(&embedding, "$1");
+        // Filter parameters are numbered after the ones bound below
+        let (from, condition, score, order_by, bound_params) = match leg {
+            SearchLeg::Vector => (
+                "documents",
+                // Only get results with some similarity
+                format!("{} > {}", similarity, MIN_SIMILARITY),
+                similarity.clone(),
+                // Order by the raw distance so the index can serve the query
+                format!("{} {} $1", embedding, metric.operator()),
+                2,
+            ),
+            SearchLeg::Lexical(_) => (
+                "documents, websearch_to_tsquery('english', $3) AS tsquery",
+                "content_tsv @@ tsquery".to_string(),
+                // Normalization 1 divides by 1 + log(length), a BM25-like length penalty
+                "ts_rank(content_tsv, tsquery, 1)".to_string(),
+                "score DESC".to_string(),
+                3,
+            ),
+        };
+
+        let mut filter_params = Vec::new();
+        let filter_condition = match &options.filter {
+            Some(filter) => format!(
+                "AND {}",
+                filter.to_sql("metadata", bound_params, &mut filter_params)
+            ),
+            None => String::new(),
+        };
+
+        let collection = self.collection.predicate_sql();
+        let sql = format!(
+            r#"
+            WITH ranked_search AS (
+                SELECT
+                    id,
+                    file_name,
+                    chunk_id,
+                    content,
+                    embedding,
+                    created_at,
+                    start_byte,
+                    end_byte,
+                    start_char,
+                    end_char,
+                    start_line,
+                    end_line,
+                    parent_id,
+                    metadata,
+                    {similarity} as similarity,
+                    {score} AS score
+                FROM {from}
+                WHERE {collection} AND {condition}
+                {filter_condition}
+                ORDER BY {order_by}
+                LIMIT $2
+            )
+            SELECT
+                s.*,
+                p.file_name AS parent_file_name,
+                p.content AS parent_content,
+                p.heading_path AS parent_heading_path,
+                p.created_at AS parent_created_at,
+                p.start_byte AS parent_start_byte,
+                p.end_byte AS parent_end_byte,
+                p.start_char AS parent_start_char,
+                p.end_char AS parent_end_char,
+                p.start_line AS parent_start_line,
+                p.end_line AS parent_end_line
+            FROM ranked_search s
+            LEFT JOIN document_parents p ON p.id = s.parent_id
+            ORDER BY s.score DESC
+            "#
+        );
+
+        let mut query = sqlx::query(&sql).bind(query_vector).bind(limit as i64);
+        if let SearchLeg::Lexical(text) = leg {
+            query = query.bind(text);
+        }
+        for param in filter_params {
+            query = match param {
+                FilterParam::Path(path) => query.bind(path),
+                FilterParam::Json(value) => query.bind(Json(value)),
+                FilterParam::Text(text) => query.bind(text),
+            };
+        }
+
+        // HNSW returns at most `ef_search` rows, so larger fetches raise it for this search
+        let limit_settings = match leg {
+            SearchLeg::Vector => self.index.search_limit_sql(limit),
+            SearchLeg::Lexical(_) => None,
+        };
+        let rows = match limit_settings {
+            Some(settings) => {
+                let mut tx = self.pool.begin().await?;
+                sqlx::query(&settings)
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to raise search candidate limit")?;
+                let rows = query
+                    .fetch_all(&mut *tx)
+                    .await
+                    .with_context(|| "Failed to execute similarity search")?;
+                tx.commit().await?;
+                rows
+            }
+            None => query
+                .fetch_all(&self.pool)
+                .await
+                .with_context(|| "Failed to execute similarity search")?,
+        };
+
+        tracing::info!("Found {} results", rows.len());
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let document = document_from_row(row);
+                let similarity: f64 = row.get("similarity");
+
+                tracing::info!(
+                    "Result: file={}, similarity={:.4}, content={}",
+                    document.file_name,
+                    similarity,
+                    document.content.chars().take(50).collect::<String>()
+                );
+
+                let result = SearchResult::new(document, similarity as f32);
+                match parent_from_row(row) {
+                    Some(parent) if options.expand_parents => result.with_parent(parent),
+                    _ => result,
+                }
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl VectorStore for PgVectorStore {
+    fn collection_name(&self) -> &str {
+        &self.collection.name
+    }
+
+    async fn insert_document(&self, chunk: DocumentChunk) -> Result<Document> {
+        let document = chunk.into_document();
+        self.collection.check_dimensions(&document.embedding)?;
+        self.insert_document_row(&self.pool, &document).await?;
+        Ok(document)
+    }
+
+    /// Inserts chunks with multi-row `INSERT`s inside one transaction, so either
+    /// all of them are stored or none
+    async fn insert_documents_batch(&self, chunks: Vec<DocumentChunk>) -> Result<Vec<Document>> {
+        let documents: Vec<Document> = chunks
+            .into_iter()
+            .map(DocumentChunk::into_document)
+            .collect();
+        for document in &documents {
+            self.collection.check_dimensions(&document.embedding)?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+        self.insert_document_rows(&mut tx, &documents).await?;
+        tx.commit()
+            .await
+            .context("Failed to commit document batch")?;
+
+        Ok(documents)
+    }
+
+    /// Stores a parent section; insert it before the chunks that reference it
+    async fn insert_parent(&self, parent: &ParentDocument) -> Result<()> {
+        self.insert_parent_row(&self.pool, parent).await
+    }
+
+    /// Atomically replaces every chunk and parent section of `source` with the
+    /// given ones, see [`Document::source`]
+    async fn upsert_document(
+        &self,
+        source: &str,
+        parents: &[ParentDocument],
+        chunks: Vec<DocumentChunk>,
+    ) -> Result<Vec<Document>> {
+        let documents: Vec<Document> = chunks
+            .into_iter()
+            .map(DocumentChunk::into_document)
+            .collect();
+        for document in &documents {
+            self.collection.check_dimensions(&document.embedding)?;
+            if document.source() != source {
+                anyhow::bail!(
+                    "Chunk of '{}' cannot be stored as part of '{}'",
+                    document.source(),
+                    source
+                );
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+        self.delete_source(&mut tx, source).await?;
+        for parent in parents {
+            self.insert_parent_row(&mut *tx, parent).await?;
+        }
+        self.insert_document_rows(&mut tx, &documents).await?;
+        tx.commit()
+            .await
+            .context("Failed to commit document upsert")?;
+
+        Ok(documents)
+    }
+
+    async fn search_similar(
+        &self,
+        query_embedding: Vec<f32>,
+        limit: usize,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        tracing::info!(
+            "PgVectorStore::search_similar: query_embedding_length={}, query_embedding_sample={:?}",
+            query_embedding.len(),
+            &query_embedding[..std::cmp::min(5, query_embedding.len())]
+        );
+
+        if query_embedding.len() != self.collection.dimensions {
+            return Err(anyhow::anyhow!(
+                "Query embedding dimension mismatch: expected {}, got {}",
+                self.collection.dimensions,
+                query_embedding.len()
+            ));
+        }
+
+        let query_vector = Vector::from(query_embedding);
+
+        // Several children may share a parent, so over-fetch before deduplicating
+        let fetch_limit = if options.expand_parents {
+            limit.saturating_mul(3)
+        } else {
+            limit
+        };
+
+        let results = match &options.hybrid {
+            None => {
+                self.run_search(SearchLeg::Vector, &query_vector, fetch_limit, options)
+                    .await?
+            }
+            Some(hybrid) => {
+                // Each leg fetches a wider candidate pool so fusion can promote hits
+                // that only rank moderately in one of them
+                let candidates = fetch_limit.saturating_mul(2);
+                let vector = self
+                    .run_search(SearchLeg::Vector, &query_vector, candidates, options)
+                    .await?;
+                let terms = lexical_terms(&hybrid.query_text);
+                let lexical = if terms.is_empty() {
+                    Vec::new()
+                } else {
+                    // OR the terms so a hit needs only some of them; ts_rank favours more
+                    let query = terms.join(" OR ");
+                    self.run_search(
+                        SearchLeg::Lexical(&query),
+                        &query_vector,
+                        candidates,
+                        options,
+                    )
+                    .await?
+                };
+                tracing::info!(
+                    "Hybrid search: {} vector and {} lexical candidates",
+                    vector.len(),
+                    lexical.len()
+                );
+
+                let mut fused = hybrid.fuse(vector, lexical);
+                fused.truncate(fetch_limit);
+                fused
+            }
+        };
+
+        if !options.expand_parents {
+            return Ok(results);
+        }
+
+        let mut results = dedupe_by_parent(results);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Deletes every chunk and parent section of `source`, returning the number of chunks
+    async fn delete_by_file(&self, source: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let deleted = self.delete_source(&mut tx, source).await?;
+        tx.commit()
+            .await
+            .context("Failed to commit document deletion")?;
+        Ok(deleted)
+    }
+
+    async fn delete_all_documents(&self) -> Result<()> {
+        sqlx::query("DELETE FROM documents WHERE collection = $1")
+            .bind(&self.collection.name)
+            .execute(&self.pool)
+            .await
+            .context("Failed to delete all documents")?;
+
+        sqlx::query("DELETE FROM document_parents WHERE collection = $1")
+            .bind(&self.collection.name)
+            .execute(&self.pool)
+            .await
+            .context("Failed to delete parent sections")?;
+
+        Ok(())
+    }
+
+    /// Every stored source file of the collection with its chunk count, ordered by source
+    async fn list_documents(&self) -> Result<Vec<DocumentInfo>> {
+        let rows = sqlx::query(&document_info_sql(""))
+            .bind(&self.collection.name)
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to list documents")?;
+
+        Ok(rows.iter().map(document_info_from_row).collect())
+    }
+
+    /// The stored source file `source`, if any
+    async fn get_document(&self, source: &str) -> Result<Option<DocumentInfo>> {
+        let row = sqlx::query(&document_info_sql(&format!("AND {} = $2", SOURCE_SQL)))
+            .bind(&self.collection.name)
+            .bind(source)
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to get document")?;
+
+        Ok(row.as_ref().map(document_info_from_row))
+    }
+
+    /// Chunks of `source` in document order
+    async fn get_chunks(&self, source: &str) -> Result<Vec<Document>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT
+                id, file_name, chunk_id, content, embedding, created_at,
+                start_byte, end_byte, start_char, end_char, start_line, end_line,
+                parent_id, metadata
+            FROM documents
+            WHERE collection = $1 AND {} = $2
+            ORDER BY chunk_id
+            "#,
+            SOURCE_SQL
+        ))
+        .bind(&self.collection.name)
+        .bind(source)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to get chunks")?;
+
+        Ok(rows.iter().map(document_from_row).collect())
+    }
+
+    async fn get_document_count(&self) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) as count FROM documents WHERE collection = $1")
+            .bind(&self.collection.name)
+            .fetch_one(&self.pool)
+            .await
+            .context("Failed to get document count")?;
+
+        Ok(row.get("count"))
+    }
+
+    /// Chunk, file and parent counts of this store's collection
+    async fn collection_stats(&self) -> Result<CollectionStats> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM documents WHERE collection = $1) AS documents,
+                (SELECT COUNT(DISTINCT {}) FROM documents WHERE collection = $1) AS files,
+                (SELECT COUNT(*) FROM document_parents WHERE collection = $1) AS parents
+            "#,
+            SOURCE_SQL
+        ))
+        .bind(&self.collection.name)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to get collection stats")?;
+
+        Ok(CollectionStats {
+            name: self.collection.name.clone(),
+            embedding_model: self.collection.embedding_model.clone(),
+            dimensions: self.collection.dimensions,
+            documents: row.get("documents"),
+            files: row.get("files"),
+            parents: row.get("parents"),
+        })
+    }
+
+    /// A store for another collection sharing this store's connection pool,
+    /// creating the collection on first use
+    async fn collection_store(&self, collection: &Collection) -> Result<Arc<dyn VectorStore>> {
+        let collection = self.create_collection(collection).await?;
+        Ok(Arc::new(Self {
+            pool: self.pool.clone(),
+            collection,
+            index: self.index.clone(),
+        }))
+    }
+
+    async fn list_collections(&self) -> Result<Vec<Collection>> {
+        let rows = sqlx::query(
+            "SELECT name, embedding_model, dimensions, created_at FROM collections ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list collections")?;
+
+        Ok(rows.iter().map(collection_from_row).collect())
+    }
+
+    /// Deletes a collection with all its chunks, parent sections and vector index
+    async fn drop_collection(&self, name: &str) -> Result<()> {
+        let Some(collection) = self.get_collection(name).await? else {
+            anyhow::bail!("Collection '{}' does not exist", name);
+        };
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM documents WHERE collection = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete collection documents")?;
+        sqlx::query("DELETE FROM document_parents WHERE collection = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete collection parent sections")?;
+        sqlx::query(&format!("DROP INDEX IF EXISTS {}", collection.index_name()))
+            .execute(&mut *tx)
+            .await
+            .context("Failed to drop collection index")?;
+        sqlx::query("DELETE FROM collections WHERE name = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete collection")?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+fn document_from_row(row: &PgRow) -> Document {
+    let embedding_vector: Vector = row.get("embedding");
+    Document {
+        id: row.get("id"),
+        file_name: row.get("file_name"),
+        chunk_id: row.get::<i32, _>("chunk_id") as usize,
+        content: row.get("content"),
+        embedding: embedding_vector.into(),
+        created_at: row.get("created_at"),
+        span: span_from_row(row, ""),
+        parent_id: row.get("parent_id"),
+        metadata: row
+            .try_get::<Json<DocumentMetadata>, _>("metadata")
+            .map(|metadata| metadata.0)
+            .unwrap_or_default(),
+    }
+}
+
+/// Per-source aggregate over a collection's chunks; `condition` narrows the rows
+fn document_info_sql(condition: &str) -> String {
+    format!(
+        r#"
+        SELECT
+            {source} AS source,
+            MIN(file_name) AS file_name,
+            MAX(metadata->>'checksum') AS content_hash,
+            COUNT(*) AS chunks,
+            MAX(created_at) AS ingested_at,
+            MAX(metadata->>'mtime') AS modified_at
+        FROM documents
+        WHERE collection = $1 {condition}
+        GROUP BY {source}
+        ORDER BY {source}
+        "#,
+        source = SOURCE_SQL
+    )
+}
+
+fn document_info_from_row(row: &PgRow) -> DocumentInfo {
+    DocumentInfo {
+        source: row.get("source"),
+        file_name: row.get("file_name"),
+        content_hash: row.get("content_hash"),
+        chunks: row.get("chunks"),
+        ingested_at: row
+            .get::<Option<DateTime<Utc>>, _>("ingested_at")
+            .unwrap_or_default(),
+        modified_at: row
+            .get::<Option<String>, _>("modified_at")
+            .and_then(|mtime| DateTime::parse_from_rfc3339(&mtime).ok())
+            .map(|mtime| mtime.with_timezone(&Utc)),
+    }
+}
+
+fn collection_from_row(row: &PgRow) -> Collection {
+    Collection {
+        name: row.get("name"),
+        embedding_model: row.get("embedding_model"),
+        dimensions: row.get::<i32, _>("dimensions") as usize,
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Reads the source span columns (named with `prefix`), which are all NULL for
+/// rows ingested before V3
+fn span_from_row(row: &PgRow, prefix: &str) -> Option<SourceSpan> {
+    let column = |name: &str| {
+        row.get::<Option<i32>, _>(format!("{}{}", prefix, name).as_str())
+            .map(|value| value as usize)
+    };
+    Some(SourceSpan {
+        start_byte: column("start_byte")?,
+        end_byte: column("end_byte")?,
+        start_char: column("start_char")?,
+        end_char: column("end_char")?,
+        start_line: column("start_line")?,
+        end_line: column("end_line")?,
+    })
+}
+
+/// Reads the joined `parent_*` columns, `None` when the chunk has no parent
+fn parent_from_row(row: &PgRow) -> Option<ParentDocument> {
+    let id = row.get::<Option<uuid::Uuid>, _>("parent_id")?;
+    let content = row.get::<Option<String>, _>("parent_content")?;
+    Some(ParentDocument {
+        id,
+        file_name: row.get("parent_file_name"),
+        content,
+        heading_path: row.get("parent_heading_path"),
+        span: span_from_row(row, "parent_"),
+        created_at: row.get("parent_created_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DocumentChunk;
+
+    // These tests require a running PostgreSQL with pgvector extension
+    // For now, we'll create stub tests that demonstrate the interface
+
+    #[tokio::test]
+    async fn should_create_vector_store_with_valid_url() {
+        // This test would fail without a real database
+        // For TDD purposes, we're designing the interface first
+        let result = PgVectorStore::new("postgresql://invalid").await;
+
+        // We expect this to fail with connection error - that's fine for TDD
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_handle_document_insertion() {
+        // Mock test - demonstrates expected interface
+        let embedding = vec![0.1; 1024]; // 1024-dimensional vector
+        let chunk = DocumentChunk::new(
+            "test.txt".to_string(),
+            0,
+            "test content".to_string(),
+            embedding,
+        );
+
+        // This would require a real database connection to work
+        // For now, we're just testing the types compile correctly
+        assert_eq!(chunk.file_name, "test.txt");
+        assert_eq!(chunk.chunk_id, 0);
+        assert_eq!(chunk.content, "test content");
+        assert_eq!(chunk.embedding.len(), 1024);
+    }
+
+    #[tokio::test]
+    async fn should_handle_similarity_search() {
+        // Mock test - demonstrates expected interface
+        let query_embedding = vec![0.2; 1024];
+
+        // This would require a real database to work
+        // For now, we're testing the interface design
+        assert_eq!(query_embedding.len(), 1024);
+        assert!(query_embedding.iter().all(|&x| x == 0.2));
+    }
+
+    #[test]
+    fn should_create_document_chunk_with_correct_dimensions() {
+        let embedding = vec![0.1; 1024];
+        let chunk = DocumentChunk::new(
+            "test.txt".to_string(),
+            1,
+            "chunk content".to_string(),
+            embedding.clone(),
+        );
+
+        let document = chunk.into_document();
+        assert_eq!(document.embedding.len(), 1024);
+        assert_eq!(document.file_name, "test.txt");
+        assert_eq!(document.chunk_id, 1);
+    }
+
+    #[test]
+    fn should_create_search_result_with_similarity_score() {
+        let embedding = vec![0.1; 1024];
+        let document = Document::new(
+            "test.txt".to_string(),
+            0,
+            "test content".to_string(),
+            embedding,
+        );
+
+        let result = SearchResult::new(document.clone(), 0.95);
+        assert_eq!(result.similarity, 0.95);
+        assert_eq!(result.document.id, document.id);
+    }
+
+    #[test]
+    fn should_handle_1024_dimensional_embeddings() {
+        let embedding_1024 = vec![0.1; 1024];
+        let chunk = DocumentChunk::new(
+            "bedrock_test.txt".to_string(),
+            0,
+            "test content for bedrock cohere".to_string(),
+            embedding_1024.clone(),
+        );
+
+        let document = chunk.into_document();
+        assert_eq!(document.embedding.len(), 1024);
+        assert_eq!(document.file_name, "bedrock_test.txt");
+    }
+
+    #[tokio::test]
+    async fn should_support_different_embedding_dimensions() {
+        // GREEN: Now this test should pass with our new dimension support
+        let embedding_1024 = vec![0.2; 1024];
+
+        // Test that similarity search interface can handle 1024 dimensions
+        assert_eq!(embedding_1024.len(), 1024);
+        assert!(embedding_1024.iter().all(|&x| x == 0.2));
+
+        // Test that VectorStore can be created with 1024 dimensions
+        let result = PgVectorStore::new_with_dimensions("postgresql://invalid", 1024).await;
+        assert!(result.is_err()); // Still fails due to invalid connection, but dimensions are supported
+    }
+
+    #[tokio::test]
+    async fn should_create_vector_store_with_1024_dimensions() {
+        // GREEN: Test for creating VectorStore with Bedrock Cohere dimensions
+        let result = PgVectorStore::new_with_dimensions("postgresql://invalid", 1024).await;
+
+        // We expect connection to fail, but the constructor should accept 1024 dimensions
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_validate_embedding_dimensions() {
+        // Test that embedding dimension validation works
+        let embedding_768 = vec![0.1; 768];
+        let embedding_1024 = vec![0.1; 1024];
+
+        assert_eq!(embedding_768.len(), 768);
+        assert_eq!(embedding_1024.len(), 1024);
+
+        // These would be validated at runtime when inserting/searching
+    }
+}
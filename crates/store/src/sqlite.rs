@@ -0,0 +1,561 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow},
+    types::Json,
+    Row, Sqlite, SqlitePool, Transaction,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::collection::{Collection, CollectionStats};
+use crate::memory::InMemoryVectorStore;
+use crate::models::{
+    Document, DocumentChunk, DocumentInfo, DocumentMetadata, ParentDocument, SearchOptions,
+    SearchResult, SourceSpan,
+};
+use crate::vector_store::VectorStore;
+
+/// Tables are created on open; the schema is small enough not to need migrations
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS collections (
+    name TEXT PRIMARY KEY,
+    embedding_model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL CHECK (dimensions > 0),
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS document_parents (
+    id BLOB PRIMARY KEY,
+    collection TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    heading_path TEXT NOT NULL,
+    span TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS documents (
+    id BLOB PRIMARY KEY,
+    collection TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    chunk_id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    span TEXT,
+    parent_id BLOB,
+    metadata TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_documents_collection ON documents (collection);
+CREATE INDEX IF NOT EXISTS idx_document_parents_collection ON document_parents (collection);
+"#;
+
+/// SQL identity of a chunk's source file, see [`Document::source`]
+const SOURCE_SQL: &str = "COALESCE(json_extract(metadata, '$.source_path'), file_name)";
+
+/// Vector store persisted in a single SQLite file, for deployments without
+/// Postgres. Rows are the source of truth; searches run against an in-memory
+/// brute-force index loaded on open and updated after every committed write,
+/// scoring its chunks in place rather than copying them per query.
+pub struct SqliteVectorStore {
+    pool: SqlitePool,
+    /// Collection this store reads and writes
+    collection: Collection,
+    /// Index view of this store's collection; its chunk map is shared by every
+    /// collection store of the database
+    index: InMemoryVectorStore,
+}
+
+impl SqliteVectorStore {
+    /// Opens the database at `database_url` (`sqlite://path`, created if missing)
+    /// and `collection` within it, creating the collection on first use
+    pub async fn new_with_collection(database_url: &str, collection: Collection) -> Result<Self> {
+        collection.validate()?;
+
+        let options = SqliteConnectOptions::from_str(database_url)
+            .with_context(|| format!("Invalid SQLite URL: {}", database_url))?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        // Reads are served from the index, so one connection is enough; it also
+        // keeps `sqlite::memory:` databases alive for the store's lifetime
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .context("Failed to open SQLite database")?;
+        sqlx::raw_sql(SCHEMA)
+            .execute(&pool)
+            .await
+            .context("Failed to create SQLite schema")?;
+
+        let started = Instant::now();
+        let index = load_index(&pool).await?;
+        tracing::info!(
+            "Loaded SQLite vector index from {} in {:?}",
+            database_url,
+            started.elapsed()
+        );
+
+        let mut store = Self {
+            pool,
+            index,
+            collection,
+        };
+        store.collection = store.create_collection(&store.collection).await?;
+        store.index = store.index.open(&store.collection);
+        Ok(store)
+    }
+
+    /// The collection this store reads and writes
+    pub fn collection(&self) -> &Collection {
+        &self.collection
+    }
+
+    /// Registers `collection`. An existing collection is reused when its
+    /// dimension and model match, and rejected otherwise.
+    pub async fn create_collection(&self, collection: &Collection) -> Result<Collection> {
+        collection.validate()?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO collections (name, embedding_model, dimensions, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&collection.name)
+        .bind(&collection.embedding_model)
+        .bind(collection.dimensions as i64)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .context("Failed to register collection")?;
+
+        let stored = self
+            .get_collection(&collection.name)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!("Collection '{}' was not registered", collection.name)
+            })?;
+        stored.ensure_compatible(collection)?;
+
+        self.index.open(&stored);
+        Ok(stored)
+    }
+
+    pub async fn get_collection(&self, name: &str) -> Result<Option<Collection>> {
+        let row = sqlx::query(
+            "SELECT name, embedding_model, dimensions, created_at FROM collections WHERE name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to read collection")?;
+
+        Ok(row.as_ref().map(collection_from_row))
+    }
+
+    async fn insert_rows(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        parents: &[ParentDocument],
+        documents: &[Document],
+    ) -> Result<()> {
+        for parent in parents {
+            sqlx::query(
+                "INSERT OR REPLACE INTO document_parents (id, collection, file_name, content, heading_path, span, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(parent.id)
+            .bind(&self.collection.name)
+            .bind(&parent.file_name)
+            .bind(&parent.content)
+            .bind(Json(&parent.heading_path))
+            .bind(parent.span.map(Json))
+            .bind(parent.created_at)
+            .execute(&mut **tx)
+            .await
+            .context("Failed to insert parent section")?;
+        }
+        for document in documents {
+            self.collection.check_dimensions(&document.embedding)?;
+            sqlx::query(
+                "INSERT INTO documents (id, collection, file_name, chunk_id, content, embedding, span, parent_id, metadata, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(document.id)
+            .bind(&self.collection.name)
+            .bind(&document.file_name)
+            .bind(document.chunk_id as i64)
+            .bind(&document.content)
+            .bind(embedding_to_blob(&document.embedding))
+            .bind(document.span.map(Json))
+            .bind(document.parent_id)
+            .bind(Json(&document.metadata))
+            .bind(document.created_at)
+            .execute(&mut **tx)
+            .await
+            .context("Failed to insert document")?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the chunks of `source` and the parent sections they reference
+    async fn delete_source(&self, tx: &mut Transaction<'_, Sqlite>, source: &str) -> Result<u64> {
+        sqlx::query(&format!(
+            "DELETE FROM document_parents WHERE id IN (SELECT parent_id FROM documents WHERE collection = ? AND {} = ?)",
+            SOURCE_SQL
+        ))
+        .bind(&self.collection.name)
+        .bind(source)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to delete parent sections")?;
+
+        let deleted = sqlx::query(&format!(
+            "DELETE FROM documents WHERE collection = ? AND {} = ?",
+            SOURCE_SQL
+        ))
+        .bind(&self.collection.name)
+        .bind(source)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to delete document chunks")?;
+
+        Ok(deleted.rows_affected())
+    }
+}
+
+#[async_trait]
+impl VectorStore for SqliteVectorStore {
+    fn collection_name(&self) -> &str {
+        &self.collection.name
+    }
+
+    async fn insert_document(&self, chunk: DocumentChunk) -> Result<Document> {
+        let mut documents = self.insert_documents_batch(vec![chunk]).await?;
+        Ok(documents.remove(0))
+    }
+
+    async fn insert_documents_batch(&self, chunks: Vec<DocumentChunk>) -> Result<Vec<Document>> {
+        let documents: Vec<Document> = chunks
+            .into_iter()
+            .map(DocumentChunk::into_document)
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        self.insert_rows(&mut tx, &[], &documents).await?;
+        tx.commit()
+            .await
+            .context("Failed to commit document batch")?;
+
+        self.index.insert_stored(&[], &documents)?;
+        Ok(documents)
+    }
+
+    async fn insert_parent(&self, parent: &ParentDocument) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        self.insert_rows(&mut tx, std::slice::from_ref(parent), &[])
+            .await?;
+        tx.commit().await?;
+
+        self.index.insert_stored(std::slice::from_ref(parent), &[])
+    }
+
+    async fn upsert_document(
+        &self,
+        source: &str,
+        parents: &[ParentDocument],
+        chunks: Vec<DocumentChunk>,
+    ) -> Result<Vec<Document>> {
+        let documents: Vec<Document> = chunks
+            .into_iter()
+            .map(DocumentChunk::into_document)
+            .collect();
+        if let Some(document) = documents.iter().find(|doc| doc.source() != source) {
+            anyhow::bail!(
+                "Chunk of '{}' cannot be stored as part of '{}'",
+                document.source(),
+                source
+            );
+        }
+
+        let mut tx = self.pool.begin().await?;
+        self.delete_source(&mut tx, source).await?;
+        self.insert_rows(&mut tx, parents, &documents).await?;
+        tx.commit()
+            .await
+            .context("Failed to commit document upsert")?;
+
+        self.index.replace_source(source, parents, &documents)?;
+        Ok(documents)
+    }
+
+    async fn search_similar(
+        &self,
+        query_embedding: Vec<f32>,
+        limit: usize,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        self.index
+            .search_similar(query_embedding, limit, options)
+            .await
+    }
+
+    async fn delete_by_file(&self, source: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let deleted = self.delete_source(&mut tx, source).await?;
+        tx.commit().await?;
+
+        self.index.delete_by_file(source).await?;
+        Ok(deleted)
+    }
+
+    async fn delete_all_documents(&self) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        delete_collection_rows(&mut tx, &self.collection.name).await?;
+        tx.commit().await?;
+
+        self.index.delete_all_documents().await
+    }
+
+    async fn list_documents(&self) -> Result<Vec<DocumentInfo>> {
+        self.index.list_documents().await
+    }
+
+    async fn get_document(&self, source: &str) -> Result<Option<DocumentInfo>> {
+        self.index.get_document(source).await
+    }
+
+    async fn get_chunks(&self, source: &str) -> Result<Vec<Document>> {
+        self.index.get_chunks(source).await
+    }
+
+    async fn get_document_count(&self) -> Result<i64> {
+        self.index.get_document_count().await
+    }
+
+    async fn collection_stats(&self) -> Result<CollectionStats> {
+        self.index.collection_stats().await
+    }
+
+    async fn collection_store(&self, collection: &Collection) -> Result<Arc<dyn VectorStore>> {
+        let collection = self.create_collection(collection).await?;
+        Ok(Arc::new(Self {
+            pool: self.pool.clone(),
+            index: self.index.open(&collection),
+            collection,
+        }))
+    }
+
+    async fn list_collections(&self) -> Result<Vec<Collection>> {
+        let rows = sqlx::query(
+            "SELECT name, embedding_model, dimensions, created_at FROM collections ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list collections")?;
+
+        Ok(rows.iter().map(collection_from_row).collect())
+    }
+
+    async fn drop_collection(&self, name: &str) -> Result<()> {
+        if self.get_collection(name).await?.is_none() {
+            anyhow::bail!("Collection '{}' does not exist", name);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        delete_collection_rows(&mut tx, name).await?;
+        sqlx::query("DELETE FROM collections WHERE name = ?")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete collection")?;
+        tx.commit().await?;
+
+        self.index.drop_collection(name).await
+    }
+}
+
+async fn delete_collection_rows(tx: &mut Transaction<'_, Sqlite>, name: &str) -> Result<()> {
+    sqlx::query("DELETE FROM documents WHERE collection = ?")
+        .bind(name)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to delete collection documents")?;
+    sqlx::query("DELETE FROM document_parents WHERE collection = ?")
+        .bind(name)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to delete collection parent sections")?;
+    Ok(())
+}
+
+/// Reads every collection with its chunks and parent sections into a fresh index
+async fn load_index(pool: &SqlitePool) -> Result<InMemoryVectorStore> {
+    let index = InMemoryVectorStore::empty();
+
+    let mut parents: HashMap<String, Vec<ParentDocument>> = HashMap::new();
+    for row in sqlx::query("SELECT * FROM document_parents")
+        .fetch_all(pool)
+        .await
+        .context("Failed to load parent sections")?
+    {
+        parents
+            .entry(row.get("collection"))
+            .or_default()
+            .push(parent_from_row(&row));
+    }
+
+    let mut documents: HashMap<String, Vec<Document>> = HashMap::new();
+    for row in sqlx::query("SELECT * FROM documents")
+        .fetch_all(pool)
+        .await
+        .context("Failed to load documents")?
+    {
+        documents
+            .entry(row.get("collection"))
+            .or_default()
+            .push(document_from_row(&row));
+    }
+
+    for row in sqlx::query("SELECT name, embedding_model, dimensions, created_at FROM collections")
+        .fetch_all(pool)
+        .await
+        .context("Failed to load collections")?
+    {
+        let collection = collection_from_row(&row);
+        index.open(&collection).insert_stored(
+            parents.get(&collection.name).map_or(&[], Vec::as_slice),
+            documents.get(&collection.name).map_or(&[], Vec::as_slice),
+        )?;
+    }
+
+    Ok(index)
+}
+
+/// Embeddings are stored as little-endian `f32`s
+fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn embedding_from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+fn collection_from_row(row: &SqliteRow) -> Collection {
+    Collection {
+        name: row.get("name"),
+        embedding_model: row.get("embedding_model"),
+        dimensions: row.get::<i64, _>("dimensions") as usize,
+        created_at: row.get("created_at"),
+    }
+}
+
+fn document_from_row(row: &SqliteRow) -> Document {
+    Document {
+        id: row.get("id"),
+        file_name: row.get("file_name"),
+        chunk_id: row.get::<i64, _>("chunk_id") as usize,
+        content: row.get("content"),
+        embedding: embedding_from_blob(row.get("embedding")),
+        created_at: row.get("created_at"),
+        span: row
+            .get::<Option<Json<SourceSpan>>, _>("span")
+            .map(|span| span.0),
+        parent_id: row.get::<Option<Uuid>, _>("parent_id"),
+        metadata: row.get::<Json<DocumentMetadata>, _>("metadata").0,
+    }
+}
+
+fn parent_from_row(row: &SqliteRow) -> ParentDocument {
+    ParentDocument {
+        id: row.get("id"),
+        file_name: row.get("file_name"),
+        content: row.get("content"),
+        heading_path: row.get::<Json<Vec<String>>, _>("heading_path").0,
+        span: row
+            .get::<Option<Json<SourceSpan>>, _>("span")
+            .map(|span| span.0),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::DEFAULT_COLLECTION;
+    use crate::conformance;
+
+    fn collection(dimensions: usize) -> Collection {
+        Collection::new(DEFAULT_COLLECTION, "test-model", dimensions)
+    }
+
+    #[tokio::test]
+    async fn should_pass_conformance_suite() {
+        let store = SqliteVectorStore::new_with_collection("sqlite::memory:", collection(4))
+            .await
+            .unwrap();
+
+        conformance::run(&store, 4).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_reload_chunks_after_reopening() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("vectors.db").display());
+        let metadata = DocumentMetadata {
+            source_path: Some("docs/leave.md".to_string()),
+            ..Default::default()
+        };
+        let parent = ParentDocument::new("leave.md".to_string(), "Leave".to_string(), vec![]);
+        let chunk =
+            DocumentChunk::new("leave.md".to_string(), 0, "Leave".to_string(), vec![1.0; 4])
+                .with_parent(parent.id)
+                .with_metadata(metadata);
+        {
+            let store = SqliteVectorStore::new_with_collection(&url, collection(4))
+                .await
+                .unwrap();
+            store
+                .upsert_document("docs/leave.md", std::slice::from_ref(&parent), vec![chunk])
+                .await
+                .unwrap();
+        }
+
+        let store = SqliteVectorStore::new_with_collection(&url, collection(4))
+            .await
+            .unwrap();
+        let results = store
+            .search_similar(
+                vec![1.0; 4],
+                5,
+                &SearchOptions {
+                    expand_parents: true,
+                    ..SearchOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.source(), "docs/leave.md");
+        assert_eq!(results[0].parent.as_ref(), Some(&parent));
+        assert_eq!(store.delete_by_file("docs/leave.md").await.unwrap(), 1);
+        assert!(SqliteVectorStore::new_with_collection(&url, collection(8))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn should_round_trip_embeddings_through_blobs() {
+        let embedding = vec![0.5, -1.25, f32::MIN_POSITIVE, 3.0];
+
+        assert_eq!(
+            embedding_from_blob(&embedding_to_blob(&embedding)),
+            embedding
+        );
+    }
+}
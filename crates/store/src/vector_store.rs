@@ -0,0 +1,72 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::collection::{Collection, CollectionStats};
+use crate::models::{
+    Document, DocumentChunk, DocumentInfo, ParentDocument, SearchOptions, SearchResult,
+};
+
+/// Vector hits at or below this similarity are never returned
+pub const MIN_SIMILARITY: f32 = 0.01;
+
+/// A backend storing embedded chunks of one collection. Every implementation
+/// must pass the [`crate::conformance`] suite.
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// Name of the collection this store reads and writes
+    fn collection_name(&self) -> &str;
+
+    async fn insert_document(&self, chunk: DocumentChunk) -> Result<Document>;
+
+    /// Inserts all chunks or none
+    async fn insert_documents_batch(&self, chunks: Vec<DocumentChunk>) -> Result<Vec<Document>>;
+
+    /// Stores a parent section; insert it before the chunks that reference it
+    async fn insert_parent(&self, parent: &ParentDocument) -> Result<()>;
+
+    /// Atomically replaces every chunk and parent section of `source` with the
+    /// given ones, see [`Document::source`]
+    async fn upsert_document(
+        &self,
+        source: &str,
+        parents: &[ParentDocument],
+        chunks: Vec<DocumentChunk>,
+    ) -> Result<Vec<Document>>;
+
+    /// Best matches for the query embedding, best first, at most `limit` and all
+    /// above [`MIN_SIMILARITY`]
+    async fn search_similar(
+        &self,
+        query_embedding: Vec<f32>,
+        limit: usize,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>>;
+
+    /// Deletes every chunk and parent section of `source`, returning the number of chunks
+    async fn delete_by_file(&self, source: &str) -> Result<u64>;
+
+    /// Deletes every chunk and parent section of the collection
+    async fn delete_all_documents(&self) -> Result<()>;
+
+    /// Every stored source file with its chunk count, ordered by source
+    async fn list_documents(&self) -> Result<Vec<DocumentInfo>>;
+
+    async fn get_document(&self, source: &str) -> Result<Option<DocumentInfo>>;
+
+    /// Chunks of `source` in document order
+    async fn get_chunks(&self, source: &str) -> Result<Vec<Document>>;
+
+    async fn get_document_count(&self) -> Result<i64>;
+
+    async fn collection_stats(&self) -> Result<CollectionStats>;
+
+    /// A store for another collection on the same backend, creating the
+    /// collection on first use
+    async fn collection_store(&self, collection: &Collection) -> Result<Arc<dyn VectorStore>>;
+
+    async fn list_collections(&self) -> Result<Vec<Collection>>;
+
+    /// Deletes a collection with all its chunks and parent sections
+    async fn drop_collection(&self, name: &str) -> Result<()>;
+}